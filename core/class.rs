@@ -1,14 +1,16 @@
 use crate::{
-    util::{self, Errors},
-    Concurrency, Properties, TypeBase, TypeDefinition, TypeMode,
+    util::{self, Errors, GenericPathList},
+    Concurrency, ConstructorOnError, Properties, Property, PropertyFlags, PropertyOverride, Signal,
+    TypeBase, TypeContext, TypeDefinition, TypeMode, VirtualMethod,
 };
 use darling::{
-    util::{Flag, PathList, SpannedValue},
+    util::{Flag, SpannedValue},
     FromMeta,
 };
-use heck::ToUpperCamelCase;
-use proc_macro2::{Span, TokenStream};
+use heck::{ToSnakeCase, ToUpperCamelCase};
+use proc_macro2::{Delimiter, Group, Span, TokenStream, TokenTree};
 use quote::{format_ident, quote, quote_spanned, ToTokens};
+use std::collections::BTreeSet;
 use syn::{parse_quote, parse_quote_spanned, spanned::Spanned};
 
 #[derive(Debug, Default, FromMeta)]
@@ -16,20 +18,29 @@ use syn::{parse_quote, parse_quote_spanned, spanned::Spanned};
 struct Attrs {
     pub name: Option<syn::Ident>,
     pub ns: Option<syn::Ident>,
+    pub gtype_name: Option<syn::LitStr>,
     pub class: Option<syn::Ident>,
     pub ext_trait: Option<syn::Ident>,
     pub impl_trait: Option<syn::Ident>,
     pub impl_ext_trait: Option<syn::Ident>,
     pub parent_trait: Option<syn::TypePath>,
     pub wrapper: Option<bool>,
+    #[darling(skip)]
+    pub wrapper_extra: Option<Group>,
     #[darling(rename = "abstract")]
     pub abstract_: SpannedValue<Flag>,
     #[darling(rename = "final")]
     pub final_: SpannedValue<Flag>,
-    pub extends: PathList,
-    pub implements: PathList,
-    pub inherits: PathList,
+    pub no_ext_trait: SpannedValue<Flag>,
+    pub derive_default: SpannedValue<Flag>,
+    pub builder: SpannedValue<Flag>,
+    pub extends: GenericPathList,
+    pub implements: GenericPathList,
+    pub inherits: GenericPathList,
     pub sync: Flag,
+    pub leak_check: Flag,
+    pub register_fn: Option<syn::Path>,
+    pub ord_by: Option<syn::LitStr>,
 }
 
 impl Attrs {
@@ -38,6 +49,40 @@ impl Attrs {
         let abstract_ = ("abstract", check_flag(&self.abstract_));
         let final_ = ("final", check_flag(&self.final_));
         only_one([&abstract_, &final_], errors);
+        if self.abstract_.is_some() {
+            let derive_default = ("derive_default", check_flag(&self.derive_default));
+            let builder = ("builder", check_flag(&self.builder));
+            disallow("abstract class", [&derive_default, &builder], errors);
+        }
+        let ns = ("ns", check_spanned(&self.ns));
+        let gtype_name = ("gtype_name", check_spanned(&self.gtype_name));
+        only_one([&ns, &gtype_name], errors);
+        if let Some(gtype_name) = &self.gtype_name {
+            if !util::is_valid_type_name(&gtype_name.value()) {
+                errors.push_spanned(
+                    gtype_name,
+                    format!("Invalid GType name '{}'. GType names must be at least three characters long, start with an ASCII letter or '_' and only contain ASCII letters, numbers, '-', '_' or '+'", gtype_name.value()),
+                );
+            }
+        }
+        if self.wrapper == Some(false) {
+            let wrapper_extra = ("wrapper_extra", check_spanned(&self.wrapper_extra));
+            disallow("class without wrapper", [&wrapper_extra], errors);
+        }
+        if self.final_.is_some() {
+            let ext_trait = ("ext_trait", check_spanned(&self.ext_trait));
+            let impl_trait = ("impl_trait", check_spanned(&self.impl_trait));
+            let impl_ext_trait = ("impl_ext_trait", check_spanned(&self.impl_ext_trait));
+            let no_ext_trait = ("no_ext_trait", check_flag(&self.no_ext_trait));
+            disallow(
+                "final class",
+                [&ext_trait, &impl_trait, &impl_ext_trait, &no_ext_trait],
+                errors,
+            );
+        } else if self.no_ext_trait.is_some() {
+            let ext_trait = ("ext_trait", check_spanned(&self.ext_trait));
+            disallow("class with `no_ext_trait`", [&ext_trait], errors);
+        }
     }
 }
 
@@ -46,7 +91,38 @@ pub struct ClassOptions(Attrs);
 
 impl ClassOptions {
     pub fn parse(tokens: TokenStream, errors: &Errors) -> Self {
-        Self(util::parse_list(tokens, errors))
+        let (tokens, wrapper_extra) = Self::extract_wrapper_extra(tokens, errors);
+        let mut attrs: Attrs = util::parse_list(tokens, errors);
+        attrs.wrapper_extra = wrapper_extra;
+        Self(attrs)
+    }
+    /// `wrapper_extra(...)` holds `glib::wrapper!` syntax like `@implements Iface` that doesn't
+    /// parse as meta items, so it is taken out before the rest goes to darling.
+    fn extract_wrapper_extra(tokens: TokenStream, errors: &Errors) -> (TokenStream, Option<Group>) {
+        let mut rest = TokenStream::new();
+        let mut wrapper_extra = None;
+        let mut iter = tokens.into_iter().peekable();
+        while let Some(tt) = iter.next() {
+            let is_extra = matches!(
+                (&tt, iter.peek()),
+                (TokenTree::Ident(ident), Some(TokenTree::Group(group)))
+                    if ident == "wrapper_extra" && group.delimiter() == Delimiter::Parenthesis
+            );
+            if !is_extra {
+                rest.extend([tt]);
+                continue;
+            }
+            if let Some(TokenTree::Group(group)) = iter.next() {
+                if wrapper_extra.is_some() {
+                    errors.push(tt.span(), "Duplicate field `wrapper_extra`");
+                }
+                wrapper_extra = Some(group);
+            }
+            if matches!(iter.peek(), Some(TokenTree::Punct(p)) if p.as_char() == ',') {
+                iter.next();
+            }
+        }
+        (rest, wrapper_extra)
     }
 }
 
@@ -54,17 +130,24 @@ impl ClassOptions {
 pub struct ClassDefinition {
     pub inner: TypeDefinition,
     pub ns: Option<syn::Ident>,
+    pub gtype_name: Option<syn::LitStr>,
     pub class: syn::Ident,
     pub ext_trait: Option<syn::Ident>,
     pub impl_trait: Option<syn::Ident>,
     pub impl_ext_trait: Option<syn::Ident>,
     pub parent_trait: Option<syn::TypePath>,
     pub wrapper: bool,
+    pub wrapper_extra: Option<TokenStream>,
     pub abstract_: bool,
     pub final_: bool,
+    pub derive_default: bool,
+    pub builder: bool,
     pub extends: Vec<syn::Path>,
     pub implements: Vec<syn::Path>,
     pub inherits: Vec<syn::Path>,
+    pub leak_check: bool,
+    pub register_fn: Option<syn::Path>,
+    pub ord_by: Option<syn::LitStr>,
 }
 
 impl ClassDefinition {
@@ -83,16 +166,24 @@ impl ClassDefinition {
         if attrs.sync.is_some() {
             inner.concurrency = Concurrency::SendSync;
         }
+        Signal::validate_concurrency(&inner.signals, inner.concurrency, errors);
+        VirtualMethod::validate_concurrency(&inner.virtual_methods, inner.concurrency, errors);
+        VirtualMethod::validate_generics(&mut inner.virtual_methods, &inner.generics, errors);
+        inner.validate_public_method_names(errors);
+        inner.validate_public_method_receivers(attrs.final_.is_some(), errors);
 
         let name = inner.name.clone();
         let final_ = attrs.final_.is_some();
-        let class = Self {
+        let mut class = Self {
             inner,
             ns: attrs.ns,
+            gtype_name: attrs.gtype_name,
             class: attrs
                 .class
                 .unwrap_or_else(|| format_ident!("{}Class", name)),
-            ext_trait: (!final_).then(|| {
+            // Without an ext trait, methods go in an inherent impl on the wrapper, so they are
+            // only callable on this exact type and not through `IsA` on subclasses.
+            ext_trait: (!final_ && attrs.no_ext_trait.is_none()).then(|| {
                 attrs
                     .ext_trait
                     .unwrap_or_else(|| format_ident!("{}Ext", name))
@@ -109,13 +200,42 @@ impl ClassDefinition {
             }),
             parent_trait: attrs.parent_trait,
             wrapper: attrs.wrapper.unwrap_or(true),
+            wrapper_extra: attrs
+                .wrapper_extra
+                .map(|extra| extra.stream())
+                .filter(|extra| !extra.is_empty()),
             abstract_: attrs.abstract_.is_some(),
             final_,
+            derive_default: attrs.derive_default.is_some(),
+            builder: attrs.builder.is_some(),
             extends: (*attrs.extends).clone(),
             implements: (*attrs.implements).clone(),
             inherits: (*attrs.inherits).clone(),
+            leak_check: attrs.leak_check.is_some(),
+            register_fn: attrs.register_fn,
+            ord_by: attrs.ord_by,
         };
 
+        // `ObjectSubclass::NAME` and the type data behind `ObjectSubclassType` can't vary between
+        // monomorphizations, so every instantiation would share the first one's GType and layout.
+        for param in class.inner.generics.type_params() {
+            errors.push_spanned(
+                &param.ident,
+                "Generic classes not supported, all instantiations would share one GType",
+            );
+        }
+
+        if class.implements.is_empty() && class.inherits.is_empty() {
+            for prop in &class.inner.properties {
+                if matches!(prop.override_, Some(PropertyOverride::AnyInterface)) {
+                    errors.push_spanned(
+                        &prop.field,
+                        "`override_iface` without a path requires `implements` or `inherits`",
+                    );
+                }
+            }
+        }
+
         if class.final_ {
             for virtual_method in &class.inner.virtual_methods {
                 errors.push_spanned(
@@ -132,8 +252,826 @@ impl ClassDefinition {
             }
         }
 
+        if let Some(ord_by) = &class.ord_by {
+            match class.ord_by_property() {
+                Some(prop) if !prop.has_getter() => errors.push_spanned(
+                    ord_by,
+                    format!(
+                        "`ord_by` requires property `{}` to have a getter",
+                        prop.name
+                    ),
+                ),
+                Some(_) => {}
+                None => {
+                    errors.push_spanned(ord_by, format!("Unknown property `{}`", ord_by.value()))
+                }
+            }
+        }
+
+        if class.derive_default {
+            class.derive_default = class.validate_derive_default(errors);
+        }
+        if class.builder && !class.inner.generics.params.is_empty() {
+            errors.push_spanned(
+                &class.inner.generics,
+                "`builder` not supported on generic class",
+            );
+            class.builder = false;
+        }
+
+        if class.leak_check {
+            class.add_leak_check_stmts();
+        }
+        class.add_default_stmts();
+        class.add_construct_after_stmts(errors);
+        class.add_transform_stmts(errors);
+
         class
     }
+    /// `derive_default` generates its own `Default` impl for the wrapper, so any other source of
+    /// one in the module would be a conflicting implementation, and it constructs without
+    /// properties, so it can't be used with required construct-only ones. Returns `false` on
+    /// conflicts so the impl is skipped instead of piling a rustc error on top of ours.
+    fn validate_derive_default(&self, errors: &Errors) -> bool {
+        let mut valid = true;
+        for method in &self.inner.public_methods {
+            if method.constructor.as_ref().map(|c| c.is_default()) == Some(true) {
+                errors.push_spanned(
+                    &method.sig,
+                    "`#[constructor(default)]` conflicts with `derive_default`",
+                );
+                valid = false;
+            }
+        }
+        for prop in self
+            .inner
+            .properties
+            .iter()
+            .filter(|p| Self::is_required(p))
+        {
+            errors.push_spanned(
+                &prop.field,
+                "construct-only property without a default conflicts with `derive_default`",
+            );
+            valid = false;
+        }
+        let items = self.inner.module.content.iter().flat_map(|c| &c.1);
+        for item in items {
+            let impl_ = match item {
+                syn::Item::Impl(impl_) => impl_,
+                _ => continue,
+            };
+            let is_default = impl_
+                .trait_
+                .as_ref()
+                .and_then(|(_, path, _)| path.segments.last())
+                .map(|s| s.ident == "Default")
+                .unwrap_or(false);
+            let is_wrapper = TypeMode::for_item_type(&impl_.self_ty) == Some(TypeMode::Wrapper);
+            if is_default && is_wrapper {
+                errors.push_spanned(
+                    &impl_.self_ty,
+                    "`Default` impl conflicts with `derive_default`",
+                );
+                valid = false;
+            }
+        }
+        valid
+    }
+    /// Construct-only properties without a default, which must be passed to construct an
+    /// instance.
+    fn is_required(prop: &Property) -> bool {
+        prop.override_.is_none()
+            && prop
+                .flags
+                .contains(PropertyFlags::WRITABLE | PropertyFlags::CONSTRUCT_ONLY)
+            && !prop.has_default()
+    }
+    fn default_impl(&self) -> Option<TokenStream> {
+        if !self.derive_default {
+            return None;
+        }
+        let glib = self.inner.glib();
+        let name = &self.inner.name;
+        let (impl_generics, type_generics, where_clause) = self.inner.generics.split_for_impl();
+        let expect = ConstructorOnError::Expect(None).handler(&parse_quote! { Self }, &glib);
+        Some(quote! {
+            impl #impl_generics ::std::default::Default for #name #type_generics #where_clause {
+                fn default() -> Self {
+                    #glib::Object::new::<Self>(&[]) #expect
+                }
+            }
+        })
+    }
+    /// A `NameBuilder` wrapping `glib::object::ObjectBuilder`, with a setter for each writable
+    /// property. Construct-only properties without a default are passed to `Name::builder`
+    /// instead, so they can't be forgotten.
+    fn builder_definition(&self) -> Option<TokenStream> {
+        if !self.builder {
+            return None;
+        }
+        let go = &self.inner.crate_path;
+        let glib = self.inner.glib();
+        let vis = &self.inner.vis;
+        let name = &self.inner.name;
+        let builder_name = format_ident!("{}Builder", name);
+        let builder_ident = syn::Ident::new("builder", Span::mixed_site());
+        let value_ident = syn::Ident::new("value", Span::mixed_site());
+        let properties = self
+            .inner
+            .properties
+            .iter()
+            .filter(|p| p.override_.is_none() && p.flags.contains(PropertyFlags::WRITABLE));
+        let (required, optional): (Vec<_>, Vec<_>) = properties.partition(|p| Self::is_required(p));
+        let required_args = required.iter().map(|p| {
+            let ident = p.name.field_name();
+            let ty = p.store_type(go);
+            quote_spanned! { p.field.span() => #ident: #ty }
+        });
+        let required_args = quote! { #(#required_args),* };
+        let required_idents = required.iter().map(|p| p.name.field_name());
+        let required_props = required.iter().map(|p| {
+            let prop_name = p.name.to_string();
+            let ident = p.name.field_name();
+            quote_spanned! { p.field.span() => .property(#prop_name, #ident) }
+        });
+        let setters = optional.iter().map(|p| {
+            let prop_name = p.name.to_string();
+            let method_name = p.name.field_name();
+            let ty = p.store_type(go);
+            quote_spanned! { p.field.span() =>
+                pub fn #method_name(self, #value_ident: #ty) -> Self {
+                    Self {
+                        #builder_ident: self.#builder_ident.property(#prop_name, #value_ident),
+                    }
+                }
+            }
+        });
+        let expect = ConstructorOnError::Expect(None).handler(&parse_quote! { #name }, &glib);
+        Some(quote! {
+            #[must_use]
+            #vis struct #builder_name {
+                #builder_ident: #glib::object::ObjectBuilder<'static, #name>,
+            }
+            impl #builder_name {
+                pub fn new(#required_args) -> Self {
+                    Self {
+                        #builder_ident: #glib::Object::builder::<#name>()
+                            #(#required_props)*,
+                    }
+                }
+                #(#setters)*
+                pub fn build(self) -> #name {
+                    self.#builder_ident.build() #expect
+                }
+            }
+            impl #name {
+                pub fn builder(#required_args) -> #builder_name {
+                    #builder_name::new(#(#required_idents),*)
+                }
+            }
+        })
+    }
+    /// `upcast_*` methods on the wrapper borrowing it as each of its parent types and interfaces.
+    /// These are inherent so they can't be ambiguous with the ones generated for parent types.
+    fn upcast_methods(&self) -> Option<TokenStream> {
+        if !self.wrapper {
+            return None;
+        }
+        let glib = self.inner.glib();
+        let name = &self.inner.name;
+        let mut names = BTreeSet::new();
+        let methods = self
+            .extends
+            .iter()
+            .chain(self.implements.iter())
+            .chain(self.inherits.iter())
+            .filter_map(|path| {
+                let last = path.segments.last()?;
+                let method_name = format_ident!(
+                    "upcast_{}",
+                    last.ident.to_string().to_snake_case(),
+                    span = last.ident.span()
+                );
+                names.insert(method_name.to_string()).then(|| {
+                    quote_spanned! { path.span() =>
+                        #[inline]
+                        pub fn #method_name(&self) -> &#path {
+                            #glib::Cast::upcast_ref::<#path>(self)
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        if methods.is_empty() {
+            return None;
+        }
+        let (impl_generics, type_generics, where_clause) = self.inner.generics.split_for_impl();
+        Some(quote! {
+            impl #impl_generics #name #type_generics #where_clause {
+                #(#methods)*
+            }
+        })
+    }
+    /// Associated functions on the subclass marked `#[transform]` are registered as GValue
+    /// transforms in `class_init`. A function like `fn f(src: S) -> D` lets any `Value` holding
+    /// an `S` be transformed into a `D`, with the source read through `FromValue` and the result
+    /// stored through `ToValue`.
+    fn add_transform_stmts(&mut self, errors: &Errors) {
+        let mut transforms = Vec::new();
+        for impl_ in self.inner.methods_items_mut() {
+            if TypeMode::for_item_type(&impl_.self_ty) != Some(TypeMode::Subclass) {
+                continue;
+            }
+            for item in &mut impl_.items {
+                if let syn::ImplItem::Method(method) = item {
+                    if let Some(attrs) = util::extract_attrs(&mut method.attrs, "transform") {
+                        for attr in &attrs {
+                            if !attr.tokens.is_empty() {
+                                errors.push_spanned(&attr.tokens, "Unexpected arguments");
+                            }
+                        }
+                        if let Some(transform) = Self::transform_types(&method.sig, errors) {
+                            transforms.push(transform);
+                        }
+                    }
+                }
+            }
+        }
+        if transforms.is_empty() {
+            return;
+        }
+        if !self.inner.generics.params.is_empty() {
+            errors.push_spanned(
+                &self.inner.generics,
+                "`#[transform]` not supported on generic classes",
+            );
+            return;
+        }
+        let glib = self.inner.glib();
+        let ty = self.inner.type_(
+            TypeMode::Subclass,
+            TypeMode::Subclass,
+            TypeContext::External,
+        );
+        let src_ident = syn::Ident::new("src", Span::mixed_site());
+        let dest_ident = syn::Ident::new("dest", Span::mixed_site());
+        for (ident, src_ty, dest_ty) in transforms {
+            let name = ident.to_string();
+            self.inner.add_custom_stmt(
+                "class_init",
+                parse_quote_spanned! { Span::mixed_site() => {
+                    unsafe extern "C" fn trampoline(
+                        #src_ident: *const #glib::gobject_ffi::GValue,
+                        #dest_ident: *mut #glib::gobject_ffi::GValue,
+                    ) {
+                        let #src_ident = &*(#src_ident as *const #glib::Value);
+                        // Panicking here would unwind into C, so log and leave `dest` unchanged
+                        let #src_ident = match #src_ident.get::<#src_ty>() {
+                            ::std::result::Result::Ok(value) => value,
+                            ::std::result::Result::Err(err) => {
+                                #glib::g_critical!(
+                                    "GLib-GObject",
+                                    "Invalid source value for transform `{}`: {}",
+                                    #name,
+                                    err,
+                                );
+                                return;
+                            }
+                        };
+                        let #dest_ident = &mut *(#dest_ident as *mut #glib::Value);
+                        *#dest_ident = #glib::ToValue::to_value(&<#ty>::#ident(#src_ident));
+                    }
+                    unsafe {
+                        #glib::gobject_ffi::g_value_register_transform_func(
+                            #glib::translate::IntoGlib::into_glib(
+                                <#src_ty as #glib::StaticType>::static_type()
+                            ),
+                            #glib::translate::IntoGlib::into_glib(
+                                <#dest_ty as #glib::StaticType>::static_type()
+                            ),
+                            ::std::option::Option::Some(trampoline),
+                        );
+                    }
+                } },
+            );
+        }
+    }
+    fn transform_types(
+        sig: &syn::Signature,
+        errors: &Errors,
+    ) -> Option<(syn::Ident, syn::Type, syn::Type)> {
+        if let Some(async_) = &sig.asyncness {
+            errors.push_spanned(async_, "Transform cannot be async");
+            return None;
+        }
+        if !sig.generics.params.is_empty() {
+            errors.push_spanned(&sig.generics, "Transform cannot be generic");
+            return None;
+        }
+        let mut inputs = sig.inputs.iter();
+        let src_ty = match (inputs.next(), inputs.next()) {
+            (Some(syn::FnArg::Typed(arg)), None) => (*arg.ty).clone(),
+            (Some(syn::FnArg::Receiver(recv)), _) => {
+                errors.push_spanned(recv, "Transform cannot take `self`");
+                return None;
+            }
+            _ => {
+                errors.push_spanned(
+                    &sig.inputs,
+                    "Transform must take exactly one argument for the source value",
+                );
+                return None;
+            }
+        };
+        let dest_ty = match &sig.output {
+            syn::ReturnType::Type(_, ty) => (**ty).clone(),
+            syn::ReturnType::Default => {
+                errors.push_spanned(sig, "Transform must return the destination value");
+                return None;
+            }
+        };
+        Some((sig.ident.clone(), src_ty, dest_ty))
+    }
+    fn add_default_stmts(&self) {
+        let go = &self.inner.crate_path;
+        let glib = self.inner.glib();
+        let imp_ident = syn::Ident::new("imp", Span::mixed_site());
+        let properties_path = self.inner.method_path("properties", TypeMode::Subclass);
+        let inits = self
+            .inner
+            .properties
+            .iter()
+            .enumerate()
+            .filter_map(|(index, p)| {
+                let pspec = quote! { &#properties_path()[#index] };
+                p.default_init(&imp_ident, &pspec, go)
+            })
+            .collect::<Vec<_>>();
+        if inits.is_empty() {
+            return;
+        }
+        let obj_ident = syn::Ident::new("obj", Span::mixed_site());
+        self.inner.add_custom_stmt(
+            "instance_init",
+            parse_quote_spanned! { Span::mixed_site() => {
+                let #imp_ident = #glib::subclass::prelude::ObjectSubclassIsExt::imp(
+                    unsafe { #obj_ident.as_ref() }
+                );
+                #(#inits)*
+            } },
+        );
+    }
+    /// Resolves `construct_after` to the index of the prerequisite property, checking that both
+    /// properties are set during construction and that the dependencies don't form a cycle.
+    fn construct_after_deps(&self, errors: &Errors) -> Vec<(usize, usize)> {
+        let properties = &self.inner.properties;
+        let is_construct = |p: &Property| {
+            p.flags
+                .intersects(PropertyFlags::CONSTRUCT | PropertyFlags::CONSTRUCT_ONLY)
+        };
+        let mut deps = Vec::new();
+        for (index, prop) in properties.iter().enumerate() {
+            let after = match &prop.construct_after {
+                Some(after) => after,
+                None => continue,
+            };
+            if !is_construct(prop) {
+                errors.push_spanned(
+                    after,
+                    "`construct_after` requires a `construct` or `construct_only` property",
+                );
+                continue;
+            }
+            let name = after.value();
+            let target = properties
+                .iter()
+                .position(|p| p.override_.is_none() && p.name.to_string() == name);
+            match target {
+                Some(target) if target == index => {
+                    errors.push_spanned(after, "Property cannot be constructed after itself");
+                }
+                Some(target) if !is_construct(&properties[target]) => {
+                    errors.push_spanned(
+                        after,
+                        format!(
+                            "Property `{}` is not a `construct` or `construct_only` property",
+                            name
+                        ),
+                    );
+                }
+                Some(target) => deps.push((index, target)),
+                None => errors.push_spanned(after, format!("Unknown property `{}`", name)),
+            }
+        }
+        for &(start, _) in &deps {
+            let mut chain = vec![start];
+            let mut current = start;
+            while let Some(&(_, next)) = deps.iter().find(|(from, _)| *from == current) {
+                if next == start {
+                    let names = chain
+                        .iter()
+                        .chain(std::iter::once(&start))
+                        .map(|i| format!("`{}`", properties[*i].name))
+                        .collect::<Vec<_>>();
+                    errors.push_spanned(
+                        properties[start].construct_after.as_ref().unwrap(),
+                        format!(
+                            "Cyclic `construct_after` dependency: {}",
+                            names.join(" -> ")
+                        ),
+                    );
+                    return Vec::new();
+                }
+                if chain.contains(&next) {
+                    break;
+                }
+                chain.push(next);
+                current = next;
+            }
+        }
+        deps
+    }
+    /// GObject sets construct properties one at a time in an order the class doesn't control. A
+    /// property with `construct_after` that arrives before its prerequisite has its value stashed
+    /// in the object's data and returns early. Once the prerequisite is set for the first time,
+    /// `set_property` is re-entered for each stashed dependent, so chains of dependencies are
+    /// applied in order. After construction both properties have been set, so later calls go
+    /// straight through.
+    fn add_construct_after_stmts(&self, errors: &Errors) {
+        let deps = self.construct_after_deps(errors);
+        if deps.is_empty() {
+            return;
+        }
+        let glib = self.inner.glib();
+        let name = self.inner.name.to_string();
+        let properties = &self.inner.properties;
+        let mark_key =
+            |index: usize| format!("gobject-construct-set-{}-{}", name, properties[index].name);
+        let defer_key = |index: usize| {
+            format!(
+                "gobject-construct-deferred-{}-{}",
+                name, properties[index].name
+            )
+        };
+        let self_ident = syn::Ident::new("self", Span::mixed_site());
+        let obj_ident = syn::Ident::new("obj", Span::mixed_site());
+        let id_ident = syn::Ident::new("id", Span::mixed_site());
+        let value_ident = syn::Ident::new("value", Span::mixed_site());
+        let pspec_ident = syn::Ident::new("pspec", Span::mixed_site());
+        let prop_id_ident = syn::Ident::new("generated_prop_id", Span::mixed_site());
+        for index in 0..properties.len() {
+            let prop_id = (index + 1) as i64;
+            if let Some(&(_, target)) = deps.iter().find(|(from, _)| *from == index) {
+                let mark = mark_key(target);
+                let defer = defer_key(index);
+                self.inner.add_custom_stmt(
+                    "set_property",
+                    parse_quote_spanned! { Span::mixed_site() =>
+                        if #prop_id_ident == #prop_id
+                            && unsafe { #glib::ObjectExt::data::<()>(#obj_ident, #mark) }.is_none()
+                        {
+                            unsafe {
+                                #glib::ObjectExt::set_data(
+                                    #obj_ident,
+                                    #defer,
+                                    ::std::clone::Clone::clone(#value_ident),
+                                );
+                            }
+                            return;
+                        }
+                    },
+                );
+            }
+            let dependents = deps
+                .iter()
+                .filter(|(_, to)| *to == index)
+                .map(|&(from, _)| {
+                    let defer = defer_key(from);
+                    let dep_name = properties[from].name.to_string();
+                    let dep_id = (from + 1) as i64;
+                    quote_spanned! { Span::mixed_site() =>
+                        if let ::std::option::Option::Some(#value_ident) = unsafe {
+                            #glib::ObjectExt::steal_data::<#glib::Value>(#obj_ident, #defer)
+                        } {
+                            let #pspec_ident = #glib::ObjectExt::find_property(#obj_ident, #dep_name)
+                                .unwrap();
+                            <Self as #glib::subclass::object::ObjectImpl>::set_property(
+                                #self_ident,
+                                #obj_ident,
+                                (#id_ident as i64 - #prop_id + #dep_id) as usize,
+                                &#value_ident,
+                                &#pspec_ident,
+                            );
+                        }
+                    }
+                })
+                .collect::<Vec<_>>();
+            if dependents.is_empty() {
+                continue;
+            }
+            let mark = mark_key(index);
+            self.inner.add_custom_stmt(
+                "set_property",
+                parse_quote_spanned! { Span::mixed_site() =>
+                    if #prop_id_ident == #prop_id
+                        && unsafe { #glib::ObjectExt::data::<()>(#obj_ident, #mark) }.is_none()
+                    {
+                        unsafe { #glib::ObjectExt::set_data(#obj_ident, #mark, ()) };
+                        <Self as #glib::subclass::object::ObjectImpl>::set_property(
+                            #self_ident,
+                            #obj_ident,
+                            #id_ident,
+                            #value_ident,
+                            #pspec_ident,
+                        );
+                        #(#dependents)*
+                        return;
+                    }
+                },
+            );
+        }
+    }
+    /// Counts live instances in debug builds. GObject always runs `dispose` before finalizing an
+    /// instance, so there is nothing to check there.
+    fn add_leak_check_stmts(&self) {
+        let go = &self.inner.crate_path;
+        let obj_ident = syn::Ident::new("obj", Span::mixed_site());
+        self.inner.add_custom_stmt(
+            "instance_init",
+            parse_quote_spanned! { Span::mixed_site() =>
+                if ::std::cfg!(debug_assertions) {
+                    #go::LeakCheck::attach(
+                        unsafe { #obj_ident.as_ref() },
+                        &self::_GENERATED_LIVE_INSTANCES,
+                    );
+                }
+            },
+        );
+    }
+    fn leak_check_definition(&self) -> Option<TokenStream> {
+        if !self.leak_check {
+            return None;
+        }
+        let go = &self.inner.crate_path;
+        let glib = self.inner.glib();
+        let name = &self.inner.name;
+        let vis = &self.inner.inner_vis;
+        Some(quote! {
+            #[doc(hidden)]
+            static _GENERATED_LIVE_INSTANCES: ::std::sync::atomic::AtomicUsize =
+                ::std::sync::atomic::AtomicUsize::new(0);
+            impl super::#name {
+                #vis fn live_instance_count() -> usize {
+                    _GENERATED_LIVE_INSTANCES.load(::std::sync::atomic::Ordering::SeqCst)
+                }
+                #[doc = "Logs a `g_warning` if any instances are still alive, returning their count."]
+                #vis fn check_leaks() -> usize {
+                    #go::LeakCheck::report(
+                        <super::#name as #glib::StaticType>::static_type().name(),
+                        &_GENERATED_LIVE_INSTANCES,
+                    )
+                }
+            }
+        })
+    }
+    /// Whether the user already has a method named `name` on the wrapper, which a generated
+    /// inherent method would either conflict with or shadow.
+    fn defines_method(&self, name: &str) -> bool {
+        self.inner.has_method(TypeMode::Wrapper, name)
+            || self
+                .inner
+                .public_methods
+                .iter()
+                .any(|m| m.sig.ident == name)
+    }
+    /// Only names of the properties declared on this type are accepted, anything else is
+    /// reported as not found without going through the property system. Overrides are accepted
+    /// by name and left to the runtime, since their flags come from the parent's pspec.
+    fn property_by_name_definition(&self) -> Option<TokenStream> {
+        if self.inner.properties.is_empty() {
+            return None;
+        }
+        let glib = self.inner.glib();
+        let name = &self.inner.name;
+        let vis = &self.inner.inner_vis;
+        let (impl_generics, type_generics, where_clause) = self.inner.generics.split_for_impl();
+        let names = |f: fn(&Property) -> bool| {
+            self.inner
+                .properties
+                .iter()
+                .filter(|p| f(p))
+                .map(|p| p.name.to_string())
+                .collect::<Vec<_>>()
+        };
+        let readable =
+            names(|p| p.override_.is_some() || p.flags.contains(PropertyFlags::READABLE));
+        let writable = names(|p| {
+            p.override_.is_some()
+                || (p.flags.contains(PropertyFlags::WRITABLE)
+                    && !p.flags.contains(PropertyFlags::CONSTRUCT_ONLY))
+        });
+        let read_only = names(|p| {
+            p.override_.is_none()
+                && (!p.flags.contains(PropertyFlags::WRITABLE)
+                    || p.flags.contains(PropertyFlags::CONSTRUCT_ONLY))
+        });
+        let name_ident = syn::Ident::new("name", Span::mixed_site());
+        let value_ident = syn::Ident::new("value", Span::mixed_site());
+        let pairs_ident = syn::Ident::new("pairs", Span::mixed_site());
+        let guard_ident = syn::Ident::new("_guard", Span::mixed_site());
+        let readable_arm = (!readable.is_empty()).then(|| {
+            quote_spanned! { Span::mixed_site() =>
+                #(#readable)|* => #glib::ObjectExt::try_property_value(self, #name_ident).ok(),
+            }
+        });
+        let writable_arm = (!writable.is_empty()).then(|| {
+            quote_spanned! { Span::mixed_site() =>
+                #(#writable)|* => ::std::result::Result::Ok(()),
+            }
+        });
+        let read_only_arm = (!read_only.is_empty()).then(|| {
+            quote_spanned! { Span::mixed_site() =>
+                #(#read_only)|* => ::std::result::Result::Err(#glib::bool_error!(
+                    "property '{}' of type '{}' is not writable",
+                    #name_ident,
+                    #glib::ObjectExt::type_(self),
+                )),
+            }
+        });
+        let check_writable = quote_spanned! { Span::mixed_site() =>
+            match #name_ident {
+                #writable_arm
+                #read_only_arm
+                _ => ::std::result::Result::Err(#glib::bool_error!(
+                    "property '{}' of type '{}' not found",
+                    #name_ident,
+                    #glib::ObjectExt::type_(self),
+                )),
+            }
+        };
+        let get = (!self.defines_method("get_property_by_name")).then(|| {
+            quote_spanned! { Span::mixed_site() =>
+                #[doc = "Returns the value of a property declared on this type, or `None` if there is no readable property with that name."]
+                #vis fn get_property_by_name(&self, #name_ident: &str) -> ::std::option::Option<#glib::Value> {
+                    match #name_ident {
+                        #readable_arm
+                        _ => ::std::option::Option::None,
+                    }
+                }
+            }
+        });
+        let set = (!self.defines_method("set_property_by_name")).then(|| {
+            quote_spanned! { Span::mixed_site() =>
+                #[doc = "Sets a property declared on this type, returning an error if there is no writable property with that name or the value has the wrong type."]
+                #vis fn set_property_by_name(
+                    &self,
+                    #name_ident: &str,
+                    #value_ident: &#glib::Value,
+                ) -> ::std::result::Result<(), #glib::BoolError> {
+                    (#check_writable)?;
+                    #glib::ObjectExt::try_set_property_from_value(self, #name_ident, #value_ident)
+                }
+            }
+        });
+        let set_all = (!self.defines_method("try_set_properties_frozen")).then(|| {
+            quote_spanned! { Span::mixed_site() =>
+                #[doc = "Sets several properties declared on this type at once, with change notifications frozen until all are applied. Every name and value is validated first, so an unknown name or mistyped value returns an error without setting anything."]
+                #[doc = ""]
+                #[doc = "Not named `set_properties`, since that would shadow `glib::ObjectExt::set_properties`, which panics on errors instead."]
+                #vis fn try_set_properties_frozen(
+                    &self,
+                    #pairs_ident: &[(&str, &dyn #glib::ToValue)],
+                ) -> ::std::result::Result<(), #glib::BoolError> {
+                    for (#name_ident, _) in #pairs_ident {
+                        let #name_ident: &str = #name_ident;
+                        (#check_writable)?;
+                    }
+                    let #guard_ident = #glib::ObjectExt::freeze_notify(self);
+                    #glib::ObjectExt::try_set_properties(self, #pairs_ident)
+                }
+            }
+        });
+        Some(quote_spanned! { Span::mixed_site() =>
+            impl #impl_generics super::#name #type_generics #where_clause {
+                #get
+                #set
+                #set_all
+            }
+        })
+    }
+    /// Skipped if the user already has a `static_type` method, which would either conflict with
+    /// this one or be shadowed by it.
+    fn static_type_definition(&self) -> Option<TokenStream> {
+        if self.defines_method("static_type") {
+            return None;
+        }
+        let glib = self.inner.glib();
+        let name = &self.inner.name;
+        let vis = &self.inner.inner_vis;
+        let (impl_generics, type_generics, where_clause) = self.inner.generics.split_for_impl();
+        Some(quote_spanned! { Span::mixed_site() =>
+            impl #impl_generics super::#name #type_generics #where_clause {
+                #[doc = "Returns the type, registering it on first use. Same as `glib::StaticType::static_type`, without needing the trait in scope."]
+                #vis fn static_type() -> #glib::Type {
+                    <Self as #glib::StaticType>::static_type()
+                }
+            }
+        })
+    }
+    fn ord_by_property(&self) -> Option<&Property> {
+        let name = self.ord_by.as_ref()?.value();
+        self.inner
+            .properties
+            .iter()
+            .find(|p| p.override_.is_none() && p.name.to_string() == name)
+    }
+    /// `glib::wrapper!` already implements `Ord` on the wrapper by object identity, so ordering
+    /// by a property goes in a separate comparison method usable with `sort_by`.
+    fn ord_by_definition(&self) -> Option<TokenStream> {
+        let ord_by = self.ord_by.as_ref()?;
+        let prop = self.ord_by_property().filter(|p| p.has_getter())?;
+        let name = &self.inner.name;
+        let vis = &self.inner.inner_vis;
+        let (impl_generics, type_generics, where_clause) = self.inner.generics.split_for_impl();
+        let method = format_ident!("cmp_by_{}", prop.name.field_name());
+        let getter = prop.getter_name();
+        let doc = format!(
+            "Compares two objects by their `{}` property. Objects with equal values compare as equal, so a stable sort keeps their relative order.",
+            prop.name
+        );
+        let self_ident = syn::Ident::new("self", Span::mixed_site());
+        let other_ident = syn::Ident::new("other", Span::mixed_site());
+        let cmp = quote_spanned! { ord_by.span() =>
+            ::std::cmp::Ord::cmp(&#self_ident.#getter(), &#other_ident.#getter())
+        };
+        Some(quote_spanned! { Span::mixed_site() =>
+            impl #impl_generics super::#name #type_generics #where_clause {
+                #[doc = #doc]
+                #vis fn #method(&#self_ident, #other_ident: &Self) -> ::std::cmp::Ordering {
+                    #cmp
+                }
+            }
+        })
+    }
+    fn connect_properties_notify_definition(&self) -> Option<TokenStream> {
+        if self.inner.properties.is_empty() {
+            return None;
+        }
+        let glib = self.inner.glib();
+        let name = &self.inner.name;
+        let vis = &self.inner.inner_vis;
+        let (impl_generics, type_generics, where_clause) = self.inner.generics.split_for_impl();
+        let concurrency = self.inner.concurrency;
+        let names = self
+            .inner
+            .properties
+            .iter()
+            .map(|p| p.name.to_string())
+            .collect::<BTreeSet<_>>();
+        let (rc, call) = match concurrency {
+            Concurrency::None => (quote! { ::std::rc::Rc }, quote! { connect_notify_local }),
+            Concurrency::SendSync => (quote! { ::std::sync::Arc }, quote! { connect_notify }),
+        };
+        let names_ident = syn::Ident::new("names", Span::mixed_site());
+        let name_ident = syn::Ident::new("name", Span::mixed_site());
+        // Names of the class's own properties are known here, only inherited ones need a lookup.
+        let func_ident = syn::Ident::new("func", Span::mixed_site());
+        Some(quote_spanned! { Span::mixed_site() =>
+            impl #impl_generics super::#name #type_generics #where_clause {
+                #[doc = "Connects `func` to the notify signal of each named property, returning one handler id per name. It runs once per notification, so changing several of the properties runs it once for each, even with notifications frozen. Panics if a name isn't a property of this class or of its ancestors and interfaces."]
+                #vis fn connect_properties_notify<
+                    ____Func: Fn(&Self, &#glib::ParamSpec) #concurrency + 'static
+                >(
+                    &self,
+                    #names_ident: &[&str],
+                    #func_ident: ____Func,
+                ) -> ::std::vec::Vec<#glib::SignalHandlerId> {
+                    let #func_ident = #rc::new(#func_ident);
+                    #names_ident
+                        .iter()
+                        .map(|#name_ident| {
+                            if !::std::matches!(*#name_ident, #(#names)|*)
+                                && #glib::ObjectExt::find_property(self, #name_ident).is_none()
+                            {
+                                ::std::panic!(
+                                    "property '{}' of type '{}' not found",
+                                    #name_ident,
+                                    #glib::ObjectExt::type_(self),
+                                );
+                            }
+                            let #func_ident = #rc::clone(&#func_ident);
+                            #glib::ObjectExt::#call(
+                                self,
+                                ::std::option::Option::Some(#name_ident),
+                                move |obj, pspec| #func_ident(obj, pspec),
+                            )
+                        })
+                        .collect()
+                }
+            }
+        })
+    }
     pub fn add_private_items(&mut self) {
         let extra = self.extra_private_items();
         self.inner.ensure_items().extend(extra);
@@ -145,8 +1083,14 @@ impl ClassDefinition {
             .chain(
                 [
                     self.properties_base_index_definition(),
+                    self.leak_check_definition(),
+                    self.property_by_name_definition(),
+                    self.connect_properties_notify_definition(),
+                    self.static_type_definition(),
+                    self.ord_by_definition(),
                     Some(self.object_subclass_impl()),
                     Some(self.object_impl_impl()),
+                    self.finalize_impl(),
                     self.class_struct_definition(),
                     self.is_subclassable_impl(),
                     self.inner.virtual_traits(
@@ -177,8 +1121,13 @@ impl ClassDefinition {
             return None;
         }
         let mut params = Vec::new();
-        if !self.extends.is_empty() {
-            let extends = &self.extends;
+        // `glib::wrapper!` always implements `IsA<glib::Object>`, listing it again would conflict.
+        let mut extends = self
+            .extends
+            .iter()
+            .filter(|path| !is_glib_object(path))
+            .peekable();
+        if extends.peek().is_some() {
             params.push(quote! { @extends #(#extends),* });
         }
         let mut implements = self
@@ -189,20 +1138,27 @@ impl ClassDefinition {
         if implements.peek().is_some() {
             params.push(quote! { @implements #(#implements),* });
         }
+        // Passed through verbatim, `glib::wrapper!` reports any syntax it doesn't accept.
+        params.extend(self.wrapper_extra.clone());
         let mod_name = &self.inner.module.ident;
         let name = &self.inner.name;
         let glib = self.inner.glib();
         let generics = &self.inner.generics;
+        let (_, type_generics, _) = generics.split_for_impl();
         let vis = &self.inner.vis;
         Some(quote! {
             #glib::wrapper! {
-                #vis struct #name #generics(ObjectSubclass<self::#mod_name::#name #generics>) #(#params),*;
+                #vis struct #name #generics(ObjectSubclass<self::#mod_name::#name #type_generics>) #(#params),*;
             }
         })
     }
+    /// Identifier bound to the `&mut` class struct inside the generated `class_init`.
+    pub fn class_init_ident() -> syn::Ident {
+        syn::Ident::new("class", Span::mixed_site())
+    }
     fn class_init_method(&self) -> Option<TokenStream> {
         let glib = self.inner.glib();
-        let class_ident = syn::Ident::new("class", Span::mixed_site());
+        let class_ident = Self::class_init_ident();
         let body = self.inner.type_init_body(&class_ident);
         let custom = self
             .inner
@@ -227,14 +1183,16 @@ impl ClassDefinition {
         }
         let name = &self.inner.name;
         let generics = &self.inner.generics;
+        let (_, type_generics, _) = generics.split_for_impl();
         let class_name = &self.class;
         let glib = self.inner.glib();
         let parent_class = if self.extends.is_empty() {
             quote! { #glib::gobject_ffi::GObjectClass }
         } else {
             let parent_type = self.parent_type_alias();
+            let parent_args = self.inner.alias_args(&self.parent_type());
             quote! {
-                <super::#parent_type as #glib::object::ObjectType>::GlibClassType
+                <super::#parent_type #parent_args as #glib::object::ObjectType>::GlibClassType
             }
         };
         let class_name = parse_quote! { #class_name };
@@ -265,10 +1223,10 @@ impl ClassDefinition {
                 #(pub #fields),*
             }
             unsafe #class_struct_head {
-                type Type = #name #generics;
+                type Type = #name #type_generics;
             }
             #deref_head {
-                type Target = #glib::Class<<#name #generics as #glib::subclass::types::ObjectSubclass>::Type>;
+                type Target = #glib::Class<<#name #type_generics as #glib::subclass::types::ObjectSubclass>::Type>;
 
                 fn deref(&self) -> &<Self as ::std::ops::Deref>::Target {
                     unsafe {
@@ -292,6 +1250,10 @@ impl ClassDefinition {
     pub fn interfaces_alias(&self) -> syn::Ident {
         format_ident!("_{}Interfaces", self.inner.name)
     }
+    fn interfaces(&self) -> TokenStream {
+        let interfaces = &self.implements;
+        quote! { (#(#interfaces,)*) }
+    }
     #[inline]
     fn object_subclass_impl(&self) -> TokenStream {
         let glib = self.inner.glib();
@@ -302,15 +1264,19 @@ impl ClassDefinition {
                 #glib::subclass::types::ObjectSubclass
             },
         );
-        let gtype_name = if let Some(ns) = &self.ns {
-            format!("{}{}", ns, name)
+        let gtype_name = if let Some(gtype_name) = &self.gtype_name {
+            gtype_name.value()
+        } else if let Some(ns) = &self.ns {
+            format!("{}{}", ns, name).to_upper_camel_case()
         } else {
-            name.to_string()
-        }
-        .to_upper_camel_case();
+            name.to_string().to_upper_camel_case()
+        };
         let abstract_ = self.abstract_;
         let parent_type = self.parent_type_alias();
+        let parent_args = self.inner.alias_args(&self.parent_type());
         let interfaces = self.interfaces_alias();
+        let interfaces_args = self.inner.alias_args(&self.interfaces());
+        let (_, type_generics, _) = self.inner.generics.split_for_impl();
         let class_name = &self.class;
         let class_struct_type = (!self.inner.virtual_methods.is_empty()).then(|| {
             quote! { type Class = #class_name; }
@@ -336,27 +1302,106 @@ impl ClassDefinition {
                 fn #ident(klass: &<Self as #glib::subclass::types::ObjectSubclass>::Class) -> Self
             }
         });
+        let (object_subclass_attr, defaults, register) = match &self.register_fn {
+            Some(register_fn) => {
+                let default_class = class_struct_type.is_none().then(|| {
+                    quote! { type Class = #glib::subclass::basic::ClassStruct<Self>; }
+                });
+                let default_new = (new.is_none() && with_class.is_none()).then(|| {
+                    quote! {
+                        fn new() -> Self {
+                            ::std::default::Default::default()
+                        }
+                    }
+                });
+                let defaults = quote! {
+                    #default_class
+                    type Instance = #glib::subclass::basic::InstanceStruct<Self>;
+                    #default_new
+                };
+                (
+                    None,
+                    Some(defaults),
+                    Some(self.register_definition(register_fn)),
+                )
+            }
+            None => (Some(quote! { #[#glib::object_subclass] }), None, None),
+        };
         quote! {
             const _: () = {
                 #[allow(unused_imports)]
                 use #glib;
-                #[#glib::object_subclass]
+                #object_subclass_attr
                 #head {
                     const NAME: &'static ::std::primitive::str = #gtype_name;
                     const ABSTRACT: bool = #abstract_;
-                    type Type = super::#name;
-                    type ParentType = super::#parent_type;
-                    type Interfaces = super::#interfaces;
+                    type Type = super::#name #type_generics;
+                    type ParentType = super::#parent_type #parent_args;
+                    type Interfaces = super::#interfaces #interfaces_args;
                     #class_struct_type
+                    #defaults
                     #class_init
                     #instance_init
                     #type_init
                     #new
                     #with_class
                 }
+                #register
             };
         }
     }
+    /// Stands in for the `ObjectSubclassType` impl normally emitted by `#[glib::object_subclass]`,
+    /// calling `register_fn::<T>()` instead of `glib::subclass::register_type::<T>()`. The
+    /// function must eventually call `register_type::<T>()` itself, since that is the only way to
+    /// fill in the type data, and return the registered `glib::Type`.
+    fn register_definition(&self, register_fn: &syn::Path) -> TokenStream {
+        let glib = self.inner.glib();
+        let name = &self.inner.name;
+        let type_head = self.inner.trait_head(
+            &parse_quote! { #name },
+            quote! { #glib::subclass::types::ObjectSubclassType },
+        );
+        let from_object_head = self.inner.trait_head(
+            &parse_quote! { #name },
+            quote! { #glib::subclass::types::FromObject },
+        );
+        let message = format!(
+            "`{}` did not register the type with `glib::subclass::register_type`",
+            util::path_to_string(register_fn)
+        );
+        quote_spanned! { Span::mixed_site() =>
+            unsafe #type_head {
+                fn type_data() -> ::std::ptr::NonNull<#glib::subclass::TypeData> {
+                    static mut DATA: #glib::subclass::TypeData =
+                        #glib::subclass::types::INIT_TYPE_DATA;
+                    unsafe { ::std::ptr::NonNull::new_unchecked(::std::ptr::addr_of_mut!(DATA)) }
+                }
+                fn type_() -> #glib::Type {
+                    static ONCE: ::std::sync::Once = ::std::sync::Once::new();
+                    ONCE.call_once(|| {
+                        let type_: #glib::Type = #register_fn::<Self>();
+                        ::std::assert_eq!(
+                            type_,
+                            unsafe { Self::type_data().as_ref().type_() },
+                            #message,
+                        );
+                    });
+                    unsafe {
+                        let type_ = Self::type_data().as_ref().type_();
+                        ::std::assert!(type_.is_valid());
+                        type_
+                    }
+                }
+            }
+            #[doc(hidden)]
+            #from_object_head {
+                type FromObjectType = <Self as #glib::subclass::types::ObjectSubclass>::Type;
+                fn from_object(obj: &Self::FromObjectType) -> &Self {
+                    <Self as #glib::subclass::types::ObjectSubclassExt>::from_instance(obj)
+                }
+            }
+        }
+    }
     pub(crate) fn properties_base_index_definition(&self) -> Option<TokenStream> {
         if self.inner.properties.is_empty()
             || (!self.inner.has_method(TypeMode::Subclass, "properties")
@@ -380,7 +1425,6 @@ impl ClassDefinition {
             quote_spanned! { Span::mixed_site() =>
                 let generated_prop_id = id as i64;
             }
-
         }
     }
     #[inline]
@@ -558,6 +1602,30 @@ impl ClassDefinition {
             }
         }
     }
+    /// glib-rs runs finalization by dropping the subclass struct and then chains up to the parent
+    /// class's `finalize` on its own, so a `finalize` method is called from a generated `Drop`
+    /// impl. The object is already disposed at that point and no wrapper is passed in.
+    fn finalize_impl(&self) -> Option<TokenStream> {
+        let has_method = self.inner.has_method(TypeMode::Subclass, "finalize");
+        let custom = self.inner.custom_stmts_for("finalize");
+        if !has_method && custom.is_none() {
+            return None;
+        }
+        let name = &self.inner.name;
+        let head = self
+            .inner
+            .trait_head(&parse_quote! { #name }, quote! { ::std::ops::Drop });
+        let self_ident = syn::Ident::new("self", Span::mixed_site());
+        let call_user_method = has_method.then(|| quote! { Self::finalize(#self_ident); });
+        Some(quote_spanned! { Span::mixed_site() =>
+            #head {
+                fn drop(&mut #self_ident) {
+                    #custom
+                    #call_user_method
+                }
+            }
+        })
+    }
     #[inline]
     fn is_subclassable_impl(&self) -> Option<TokenStream> {
         if self.final_ {
@@ -602,6 +1670,9 @@ impl ToTokens for ClassDefinition {
         let mod_name = &module.ident;
 
         let wrapper = self.wrapper();
+        let default_impl = self.default_impl();
+        let builder = self.builder_definition();
+        let upcasts = self.upcast_methods();
         let use_ext = self.ext_trait.as_ref().and_then(|ext| {
             self.inner
                 .public_method_definitions(self.final_)
@@ -630,24 +1701,37 @@ impl ToTokens for ClassDefinition {
         });
         let parent_type_ident = self.parent_type_alias();
         let parent_type = self.parent_type();
+        let parent_args = self.inner.alias_args(&parent_type);
         let interfaces_ident = self.interfaces_alias();
-        let interfaces = &self.implements;
+        let interfaces = self.interfaces();
+        let interfaces_args = self.inner.alias_args(&interfaces);
 
         let class = quote! {
             #module
             #wrapper
+            #default_impl
+            #builder
+            #upcasts
             #use_ext
             #use_impl
             #use_impl_ext
             #[doc(hidden)]
-            type #parent_type_ident = #parent_type;
+            type #parent_type_ident #parent_args = #parent_type;
             #[doc(hidden)]
-            type #interfaces_ident = (#(#interfaces,)*);
+            type #interfaces_ident #interfaces_args = #interfaces;
         };
         class.to_tokens(tokens);
     }
 }
 
+fn is_glib_object(path: &syn::Path) -> bool {
+    let mut segments = path.segments.iter().rev();
+    matches!(
+        (segments.next(), segments.next()),
+        (Some(last), Some(module)) if last.ident == "Object" && module.ident == "glib"
+    )
+}
+
 pub fn derived_class_properties(
     input: &syn::DeriveInput,
     go: &syn::Path,
@@ -673,10 +1757,14 @@ pub fn derived_class_properties(
 
     let mut items = Vec::new();
     for (index, prop) in properties.iter().enumerate() {
+        if let (Some(vis), Some(_)) = (&prop.vis, &trait_name) {
+            errors.push_spanned(vis, "`vis` on derived properties requires `final_type`");
+        }
+        let vis = &prop.vis;
         for item in
             prop.method_definitions(index, &wrapper_ty, Concurrency::None, &properties_path, go)
         {
-            items.push(item);
+            items.push(quote! { #vis #item });
         }
     }
 
@@ -710,7 +1798,7 @@ pub fn derived_class_properties(
         }
     };
 
-    let defs = properties.iter().map(|p| p.definition(go));
+    let defs = properties.iter().map(|p| p.definition(&wrapper_ty, go));
     let access = if base == TypeBase::Class {
         let set_impls = properties
             .iter()
@@ -721,25 +1809,31 @@ pub fn derived_class_properties(
             .enumerate()
             .filter_map(|(index, prop)| prop.get_impl(index, None, go));
         let unimplemented = ClassDefinition::unimplemented_property(&glib);
+        let self_ident = syn::Ident::new("self", Span::mixed_site());
+        let obj_ident = syn::Ident::new("obj", Span::mixed_site());
+        let id_ident = syn::Ident::new("id", Span::mixed_site());
+        let value_ident = syn::Ident::new("value", Span::mixed_site());
+        let pspec_ident = syn::Ident::new("pspec", Span::mixed_site());
+        let prop_id_ident = syn::Ident::new("generated_prop_id", Span::mixed_site());
         Some(quote! {
             fn derived_set_property(
-                &self,
-                obj: &<Self as #glib::subclass::types::ObjectSubclass>::Type,
-                id: usize,
-                value: &#glib::Value,
-                pspec: &#glib::ParamSpec
+                &#self_ident,
+                #obj_ident: &<Self as #glib::subclass::types::ObjectSubclass>::Type,
+                #id_ident: usize,
+                #value_ident: &#glib::Value,
+                #pspec_ident: &#glib::ParamSpec
             ) {
-                let properties = #properties_path();
+                let #prop_id_ident = #id_ident as i64;
                 #(#set_impls)*
                 #unimplemented
             }
             fn derived_get_property(
-                &self,
-                obj: &<Self as #glib::subclass::types::ObjectSubclass>::Type,
-                id: usize,
-                pspec: &#glib::ParamSpec
+                &#self_ident,
+                #obj_ident: &<Self as #glib::subclass::types::ObjectSubclass>::Type,
+                #id_ident: usize,
+                #pspec_ident: &#glib::ParamSpec
             ) -> #glib::Value {
-                let properties = #properties_path();
+                let #prop_id_ident = #id_ident as i64;
                 #(#get_impls)*
                 #unimplemented
             }
@@ -1,11 +1,11 @@
 use crate::{
-    util::{self, Errors},
-    Concurrency, TypeBase, TypeDefinition, TypeMode,
+    util::{self, Errors, GenericPathList},
+    Concurrency, Signal, TypeBase, TypeDefinition, TypeMode, VirtualMethod,
 };
-use darling::{util::{PathList, Flag}, FromMeta};
+use darling::{util::Flag, FromMeta};
 use heck::ToUpperCamelCase;
 use proc_macro2::{Span, TokenStream};
-use quote::{format_ident, quote, ToTokens};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
 use syn::{parse_quote, parse_quote_spanned};
 
 #[derive(Debug, Default, FromMeta)]
@@ -18,7 +18,7 @@ struct Attrs {
     pub impl_ext_trait: Option<syn::Ident>,
     pub parent_trait: Option<syn::TypePath>,
     pub wrapper: Option<bool>,
-    pub requires: PathList,
+    pub requires: GenericPathList,
     pub sync: Flag,
 }
 
@@ -58,6 +58,11 @@ impl InterfaceDefinition {
         if attrs.sync.is_some() {
             inner.concurrency = Concurrency::SendSync;
         }
+        Signal::validate_concurrency(&inner.signals, inner.concurrency, errors);
+        VirtualMethod::validate_concurrency(&inner.virtual_methods, inner.concurrency, errors);
+        VirtualMethod::validate_generics(&mut inner.virtual_methods, &inner.generics, errors);
+        inner.validate_public_method_names(errors);
+        inner.validate_public_method_receivers(false, errors);
 
         let name = inner.name.clone();
         Self {
@@ -141,10 +146,11 @@ impl InterfaceDefinition {
         let name = &self.inner.name;
         let glib = self.inner.glib();
         let generics = &self.inner.generics;
+        let (_, type_generics, _) = generics.split_for_impl();
         let vis = &self.inner.vis;
         Some(quote! {
             #glib::wrapper! {
-                #vis struct #name #generics(ObjectInterface<self::#mod_name::#name #generics>) #requires;
+                #vis struct #name #generics(ObjectInterface<self::#mod_name::#name #type_generics>) #requires;
             }
         })
     }
@@ -190,6 +196,10 @@ impl InterfaceDefinition {
     pub fn prerequisites_alias(&self) -> syn::Ident {
         format_ident!("_{}Prerequisites", self.inner.name)
     }
+    fn prerequisites(&self) -> TokenStream {
+        let requires = &self.requires;
+        quote! { (#(#requires,)*) }
+    }
     #[inline]
     fn object_interface_impl(&self) -> TokenStream {
         let glib = self.inner.glib();
@@ -207,6 +217,7 @@ impl InterfaceDefinition {
         }
         .to_upper_camel_case();
         let prerequisites = self.prerequisites_alias();
+        let prerequisites_args = self.inner.alias_args(&self.prerequisites());
         let interface_init = self.interface_init_method();
         let properties = self.inner.properties_method();
         let signals = self.inner.signals_method();
@@ -215,22 +226,54 @@ impl InterfaceDefinition {
                 fn #ident(type_: &mut #glib::subclass::types::InitializingType<Self>)
             }
         });
+        // `#[glib::object_interface]` doesn't support generics
+        let (object_interface_attr, register) = match self.inner.generics.params.is_empty() {
+            true => (Some(quote! { #[#glib::object_interface] }), None),
+            false => (None, Some(self.register_definition())),
+        };
         quote! {
             const _: () = {
                 #[allow(unused_imports)]
                 use #glib;
-                #[#glib::object_interface]
+                #object_interface_attr
                 unsafe #head {
                     const NAME: &'static ::std::primitive::str = #gtype_name;
-                    type Prerequisites = super::#prerequisites;
+                    type Prerequisites = super::#prerequisites #prerequisites_args;
                     #interface_init
                     #properties
                     #signals
                     #type_init
                 }
+                #register
             };
         }
     }
+    /// All instantiations of a generic interface share the same static, and so the same GType.
+    fn register_definition(&self) -> TokenStream {
+        let glib = self.inner.glib();
+        let name = &self.inner.name;
+        let head = self.inner.trait_head(
+            &parse_quote! { #name },
+            quote! { #glib::subclass::interface::ObjectInterfaceType },
+        );
+        quote_spanned! { Span::mixed_site() =>
+            unsafe #head {
+                fn type_() -> #glib::Type {
+                    static ONCE: ::std::sync::Once = ::std::sync::Once::new();
+                    static mut TYPE: #glib::Type = #glib::Type::INVALID;
+                    ONCE.call_once(|| {
+                        let type_ = #glib::subclass::register_interface::<Self>();
+                        unsafe {
+                            TYPE = type_;
+                        }
+                    });
+                    let type_ = unsafe { TYPE };
+                    ::std::assert!(type_.is_valid());
+                    type_
+                }
+            }
+        }
+    }
     #[inline]
     fn is_implementable_impl(&self) -> TokenStream {
         let glib = self.inner.glib();
@@ -302,7 +345,8 @@ impl ToTokens for InterfaceDefinition {
             }
         });
         let requires_ident = self.prerequisites_alias();
-        let requires = &self.requires;
+        let requires_args = self.inner.alias_args(&self.prerequisites());
+        let requires = self.prerequisites();
 
         let iface = quote! {
             #module
@@ -312,7 +356,7 @@ impl ToTokens for InterfaceDefinition {
             #vis use #mod_name::#impl_;
             #use_impl_ext
             #[doc(hidden)]
-            type #requires_ident = (#(#requires,)*);
+            type #requires_ident #requires_args = #requires;
         };
         iface.to_tokens(tokens);
     }
@@ -19,7 +19,7 @@ use syn::{parse_quote, parse_quote_spanned, spanned::Spanned};
 #[darling(default, attributes(properties))]
 pub(crate) struct PropertiesAttrs {
     pod: Flag,
-    final_type: Option<syn::Ident>,
+    final_type: Option<syn::Path>,
     interface: SpannedValue<Flag>,
     data: darling::ast::Data<darling::util::Ignored, PropertyAttrs>,
 }
@@ -47,7 +47,14 @@ struct PropertyAttrs {
     get: SpannedValue<Option<PropertyPermission>>,
     set: SpannedValue<Option<PropertyPermission>>,
     borrow: SpannedValue<Flag>,
+    snapshot: SpannedValue<Flag>,
+    downcast: Option<syn::Type>,
+    no_accessors: SpannedValue<Flag>,
+    value_accessor: SpannedValue<Flag>,
+    bind: SpannedValue<Flag>,
+    atomic: Option<SpannedValue<AtomicOrdering>>,
     construct: SpannedValue<Option<bool>>,
+    read_write_construct: SpannedValue<Flag>,
     construct_only: SpannedValue<Option<bool>>,
     lax_validation: SpannedValue<Option<bool>>,
     user_1: SpannedValue<Option<bool>>,
@@ -62,6 +69,9 @@ struct PropertyAttrs {
     deprecated: SpannedValue<Option<bool>>,
     notify: Option<bool>,
     connect_notify: Option<bool>,
+    changed_signal: Option<syn::LitStr>,
+    #[darling(rename = "vis")]
+    vis_: Option<syn::Visibility>,
     name: Option<syn::LitStr>,
     nick: Option<syn::LitStr>,
     blurb: Option<syn::LitStr>,
@@ -70,14 +80,49 @@ struct PropertyAttrs {
     flags: SpannedValue<Flag>,
     boxed: SpannedValue<Flag>,
     object: SpannedValue<Flag>,
+    weak: SpannedValue<Flag>,
     computed: SpannedValue<Flag>,
     storage: Option<SpannedValue<PropertyStorageAttr>>,
     #[darling(rename = "abstract")]
     abstract_: SpannedValue<Flag>,
     override_class: Option<syn::Path>,
-    override_iface: Option<syn::Path>,
+    override_iface: Option<SpannedValue<OverrideTarget>>,
+    default: Option<InnerExpr>,
+    minimum: Option<InnerExpr>,
+    maximum: Option<InnerExpr>,
+    parent_default: Option<InnerExpr>,
+    construct_after: Option<syn::LitStr>,
     builder_defaults: Option<syn::ExprArray>,
-    builder: SpannedValue<HashMap<syn::Ident, InnerExpr>>,
+    builder: SpannedValue<BuilderAttr>,
+}
+
+/// Either `builder(key = value, ...)`, calling each method on the pspec builder, or
+/// `builder = "|b| ..."`, a closure taking the builder and returning it.
+#[derive(Debug, Default)]
+struct BuilderAttr {
+    props: HashMap<syn::Ident, InnerExpr>,
+    closure: Option<syn::ExprClosure>,
+}
+
+impl FromMeta for BuilderAttr {
+    fn from_list(items: &[syn::NestedMeta]) -> darling::Result<Self> {
+        Ok(Self {
+            props: FromMeta::from_list(items)?,
+            closure: None,
+        })
+    }
+    fn from_string(value: &str) -> darling::Result<Self> {
+        let closure = syn::parse_str::<syn::ExprClosure>(value)?;
+        if closure.inputs.len() != 1 {
+            return Err(darling::Error::custom(
+                "builder closure must take exactly one argument",
+            ));
+        }
+        Ok(Self {
+            props: HashMap::new(),
+            closure: Some(closure),
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -110,6 +155,92 @@ impl FromMeta for InnerExpr {
     }
 }
 
+/// Cells and wrappers that can hold a property value.
+const STORAGE_WRAPPER_TYPES: &[&str] = &[
+    "Cell",
+    "RefCell",
+    "Mutex",
+    "RwLock",
+    "OnceCell",
+    "SyncOnceCell",
+    "OnceBox",
+    "PhantomData",
+    "ConstructCell",
+];
+
+/// Store types that implement `ParamStoreWrite`, besides the atomics.
+const WRITABLE_STORAGE_TYPES: &[&str] = &[
+    "Cell",
+    "RefCell",
+    "Mutex",
+    "RwLock",
+    "OnceCell",
+    "SyncOnceCell",
+    "OnceBox",
+    "OnceBool",
+    "PhantomData",
+    "WeakRef",
+    "ConstructCell",
+    "ConstructDefaultCell",
+    "ConstructOnlyCell",
+    "ConstructOnlyDefaultCell",
+    "WeakCell",
+    "DowngradeCell",
+    "SnapshotCell",
+];
+
+/// Whether the value type of a property field has a numeric pspec, looking through the cells
+/// and wrappers that can hold a property value.
+fn is_numeric_type(ty: &syn::Type) -> bool {
+    const NUMERIC_TYPES: &[&str] = &[
+        "i8",
+        "i32",
+        "i64",
+        "u8",
+        "u32",
+        "u64",
+        "f32",
+        "f64",
+        "ILong",
+        "ULong",
+        "UChar",
+        "NonZeroI8",
+        "NonZeroI32",
+        "NonZeroI64",
+        "NonZeroU8",
+        "NonZeroU32",
+        "NonZeroU64",
+        "AtomicI8",
+        "AtomicI32",
+        "AtomicI64",
+        "AtomicU8",
+        "AtomicU32",
+        "AtomicU64",
+    ];
+    let segment = match ty {
+        syn::Type::Path(p) => match p.path.segments.last() {
+            Some(segment) => segment,
+            None => return false,
+        },
+        syn::Type::Group(g) => return is_numeric_type(&g.elem),
+        syn::Type::Paren(p) => return is_numeric_type(&p.elem),
+        _ => return false,
+    };
+    if NUMERIC_TYPES.iter().any(|t| segment.ident == t) {
+        return true;
+    }
+    if !STORAGE_WRAPPER_TYPES.iter().any(|t| segment.ident == t) {
+        return false;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(syn::GenericArgument::Type(ty)) => is_numeric_type(ty),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
 impl PropertyAttrs {
     fn name(&self, index: usize) -> PropertyName {
         if let Some(name) = &self.name {
@@ -127,7 +258,7 @@ impl PropertyAttrs {
             PropertyType::Flags
         } else if self.boxed.is_some() {
             PropertyType::Boxed
-        } else if self.object.is_some() {
+        } else if self.object.is_some() || self.weak.is_some() {
             PropertyType::Object
         } else {
             PropertyType::Unspecified
@@ -155,9 +286,10 @@ impl PropertyAttrs {
         if let Some(path) = &self.override_class {
             Some(PropertyOverride::Class(path.clone()))
         } else {
-            self.override_iface
-                .as_ref()
-                .map(|path| PropertyOverride::Interface(path.clone()))
+            self.override_iface.as_ref().map(|target| match &**target {
+                OverrideTarget::Infer => PropertyOverride::AnyInterface,
+                OverrideTarget::Path(path) => PropertyOverride::Interface(path.clone()),
+            })
         }
     }
     fn flags(&self, pod: bool) -> PropertyFlags {
@@ -170,7 +302,10 @@ impl PropertyAttrs {
             PropertyFlags::WRITABLE,
             (*self.set).as_ref().map(|s| s.is_allowed()).unwrap_or(pod),
         );
-        flags.set(PropertyFlags::CONSTRUCT, self.construct.unwrap_or(false));
+        flags.set(
+            PropertyFlags::CONSTRUCT,
+            self.construct.unwrap_or(false) || self.read_write_construct.is_some(),
+        );
         flags.set(
             PropertyFlags::CONSTRUCT_ONLY,
             self.construct_only.unwrap_or(false),
@@ -213,6 +348,19 @@ impl PropertyAttrs {
         } else if !field.attrs.iter().any(|a| a.path.is_ident("property")) {
             self.skip = SpannedValue::new(Flag::present(), Span::call_site());
         }
+        if self.read_write_construct.is_some() {
+            let span = self.read_write_construct.span();
+            if self.get.is_none() {
+                self.get = SpannedValue::new(Some(PropertyPermission::Allow), span);
+            }
+            if self.set.is_none() {
+                self.set = SpannedValue::new(Some(PropertyPermission::Allow), span);
+            }
+        }
+        if self.construct_only.unwrap_or(false) && self.set.is_none() {
+            let span = self.construct_only.span();
+            self.set = SpannedValue::new(Some(PropertyPermission::Allow), span);
+        }
         let computed = self.computed.is_some();
         if let Some(get) = self.get.as_mut() {
             get.normalize(computed);
@@ -263,6 +411,7 @@ impl PropertyAttrs {
         let flags = ("flags", check_flag(&self.flags));
         let boxed = ("boxed", check_flag(&self.boxed));
         let object = ("object", check_flag(&self.object));
+        let weak = ("weak", check_flag(&self.weak));
         let override_class = (
             "override_class",
             self.override_class.as_ref().map(|o| o.span()),
@@ -274,6 +423,18 @@ impl PropertyAttrs {
         let storage = ("storage", self.storage.as_ref().map(|s| s.storage.span()));
         let abstract_ = ("abstract", check_flag(&self.abstract_));
         let computed = ("computed", check_flag(&self.computed));
+        let atomic = ("atomic", self.atomic.as_ref().map(|a| a.span()));
+        let borrow = ("borrow", check_flag(&self.borrow));
+        let snapshot = ("snapshot", check_flag(&self.snapshot));
+        let downcast = ("downcast", check_spanned(&self.downcast));
+        let no_accessors = ("no_accessors", check_flag(&self.no_accessors));
+        let value_accessor = ("value_accessor", check_flag(&self.value_accessor));
+        let bind = ("bind", check_flag(&self.bind));
+        let changed_signal = (
+            "changed_signal",
+            self.changed_signal.as_ref().map(|s| s.span()),
+        );
+        let vis = ("vis", check_spanned(&self.vis_));
         let write_only = (
             "write-only",
             (*self.get)
@@ -293,8 +454,45 @@ impl PropertyAttrs {
                 matches!(a, PropertyPermission::AllowNoMethod).then(|| self.set.span())
             }),
         );
+        let closure_getter = (
+            "get = \"|this| ...\"",
+            (*self.get).as_ref().and_then(|a| {
+                matches!(a, PropertyPermission::AllowClosure(_)).then(|| self.get.span())
+            }),
+        );
+        let closure_setter = (
+            "set = \"|this, value| ...\"",
+            (*self.set).as_ref().and_then(|a| {
+                matches!(a, PropertyPermission::AllowClosure(_)).then(|| self.set.span())
+            }),
+        );
+        let default = ("default", self.default.as_ref().map(|d| d.0.span()));
+        let minimum = ("minimum", self.minimum.as_ref().map(|m| m.0.span()));
+        let maximum = ("maximum", self.maximum.as_ref().map(|m| m.0.span()));
+        let builder_key = |name: &'static str, key: &str| {
+            (
+                name,
+                self.builder
+                    .props
+                    .keys()
+                    .find(|k| *k == key)
+                    .map(|k| k.span()),
+            )
+        };
+        let default_value = builder_key("builder(default_value)", "default_value");
+        let builder_minimum = builder_key("builder(minimum)", "minimum");
+        let builder_maximum = builder_key("builder(maximum)", "maximum");
+        let parent_default = (
+            "parent_default",
+            self.parent_default.as_ref().map(|d| d.0.span()),
+        );
+        let construct_after = ("construct_after", check_spanned(&self.construct_after));
         let construct = ("construct", check_bool(&self.construct));
         let construct_only = ("construct_only", check_bool(&self.construct_only));
+        let read_write_construct = (
+            "read_write_construct",
+            check_flag(&self.read_write_construct),
+        );
         let lax_validation = ("lax_validation", check_bool(&self.lax_validation));
         let user_1 = ("user_1", check_bool(&self.user_1));
         let user_2 = ("user_2", check_bool(&self.user_2));
@@ -310,12 +508,72 @@ impl PropertyAttrs {
         let blurb = ("blurb", self.blurb.as_ref().map(|b| b.span()));
         let builder = (
             "builder",
-            (!self.builder.is_empty()).then(|| self.builder.span()),
+            (!self.builder.props.is_empty() || self.builder.closure.is_some())
+                .then(|| self.builder.span()),
         );
 
-        only_one([&enum_, &flags, &boxed, &object], errors);
+        only_one([&enum_, &flags, &boxed, &object, &weak], errors);
+
+        if let Some(span) = weak.1 {
+            let is_weak = match &field.ty {
+                syn::Type::Path(p) => p
+                    .path
+                    .segments
+                    .last()
+                    .map(|s| s.ident == "WeakRef" || s.ident == "WeakCell")
+                    .unwrap_or(false),
+                _ => false,
+            };
+            if !is_weak {
+                errors.push(
+                    span,
+                    "`weak` requires a field of type `glib::WeakRef<T>` or `WeakCell<T>`",
+                );
+            }
+        }
         only_one([&override_class, &override_iface], errors);
-        only_one([&storage, &abstract_, &computed], errors);
+        only_one(
+            [&storage, &abstract_, &computed, &atomic, &snapshot],
+            errors,
+        );
+        only_one([&atomic, &borrow], errors);
+        only_one([&atomic, &downcast], errors);
+        only_one([&snapshot, &borrow], errors);
+        only_one([&snapshot, &downcast], errors);
+        only_one([&no_accessors, &value_accessor], errors);
+        only_one([&no_accessors, &bind], errors);
+        only_one([&construct, &read_write_construct], errors);
+        only_one([&construct_only, &read_write_construct], errors);
+
+        if let Some(atomic) = &self.atomic {
+            const ATOMIC_TYPES: &[&str] = &[
+                "AtomicBool",
+                "AtomicI8",
+                "AtomicI32",
+                "AtomicI64",
+                "AtomicU8",
+                "AtomicU32",
+                "AtomicU64",
+            ];
+            let is_atomic = match &field.ty {
+                syn::Type::Path(p) => p
+                    .path
+                    .segments
+                    .last()
+                    .map(|s| s.arguments.is_empty() && ATOMIC_TYPES.iter().any(|t| s.ident == t))
+                    .unwrap_or(false),
+                _ => false,
+            };
+            if !is_atomic {
+                errors.push(
+                    atomic.span(),
+                    format!(
+                        "`atomic` requires a field of type {}",
+                        ATOMIC_TYPES.join(", ")
+                    ),
+                );
+            }
+        }
 
         if interface.1.is_some() {
             disallow(
@@ -324,8 +582,29 @@ impl PropertyAttrs {
                     &storage,
                     &abstract_,
                     &computed,
+                    &atomic,
                     &custom_getter,
                     &custom_setter,
+                    &closure_getter,
+                    &closure_setter,
+                    &changed_signal,
+                    &vis,
+                    &snapshot,
+                    &construct_after,
+                ],
+                errors,
+            );
+        }
+
+        if write_only.1.is_some() {
+            disallow(
+                "write-only property",
+                [
+                    &downcast,
+                    &snapshot,
+                    &value_accessor,
+                    &bind,
+                    &changed_signal,
                 ],
                 errors,
             );
@@ -339,11 +618,18 @@ impl PropertyAttrs {
                     &abstract_,
                     &custom_getter,
                     &custom_setter,
+                    &closure_getter,
+                    &closure_setter,
+                    &value_accessor,
+                    &bind,
                     &nick,
                     &blurb,
                     &builder,
+                    &default,
                     &construct,
                     &construct_only,
+                    &construct_after,
+                    &read_write_construct,
                     &lax_validation,
                     &user_1,
                     &user_2,
@@ -358,10 +644,117 @@ impl PropertyAttrs {
                 ],
                 errors,
             );
+            if self.parent_default.is_none() {
+                disallow(
+                    "overridden property without `parent_default`",
+                    [&minimum, &maximum],
+                    errors,
+                );
+            }
+        }
+
+        if self.override_class.is_none() && self.override_iface.is_none() {
+            disallow("non-overridden property", [&parent_default], errors);
+        }
+        only_one([&parent_default, &abstract_, &computed], errors);
+        only_one([&default, &default_value], errors);
+        only_one([&minimum, &builder_minimum], errors);
+        only_one([&maximum, &builder_maximum], errors);
+
+        if let Some(span) = minimum.1.or(maximum.1) {
+            if !is_numeric_type(&field.ty) {
+                errors.push(
+                    span,
+                    "`minimum` and `maximum` require a numeric property type",
+                );
+            }
+        }
+
+        if self.abstract_.is_some() {
+            disallow(
+                "abstract property",
+                [&changed_signal, &closure_getter, &closure_setter],
+                errors,
+            );
+        }
+        only_one([&closure_getter, &atomic], errors);
+        only_one([&closure_getter, &snapshot], errors);
+        only_one([&closure_setter, &atomic], errors);
+        only_one([&closure_setter, &lax_validation], errors);
+
+        // Only fields written by the generated setter need interior mutability. The store traits
+        // are implemented for a fixed set of types, so anything else would fail later with an
+        // unspanned trait error.
+        let custom_setter = matches!(
+            *self.set,
+            Some(
+                PropertyPermission::AllowCustom(_)
+                    | PropertyPermission::AllowClosure(_)
+                    | PropertyPermission::AllowCustomDefault
+            )
+        );
+        if self.skip.is_none()
+            && base == TypeBase::Class
+            && self.storage.is_none()
+            && self.computed.is_none()
+            && self.abstract_.is_none()
+            && self.atomic.is_none()
+            && !custom_setter
+            && self.flags(pod).contains(PropertyFlags::WRITABLE)
+        {
+            let is_storage = match &field.ty {
+                syn::Type::Path(p) if p.qself.is_none() => p
+                    .path
+                    .segments
+                    .last()
+                    .map(|s| {
+                        let ident = s.ident.to_string();
+                        ident.starts_with("Atomic")
+                            || WRITABLE_STORAGE_TYPES.contains(&ident.as_str())
+                    })
+                    .unwrap_or(false),
+                _ => false,
+            };
+            if !is_storage {
+                errors.push_spanned(
+                    &field.ty,
+                    "Writable property field must be a storage type with interior mutability like `Cell<T>`, `RefCell<T>` or `Mutex<T>`",
+                );
+            }
+        }
+
+        let read_only = (*self.set)
+            .as_ref()
+            .map(|a| !a.is_allowed())
+            .unwrap_or(!pod);
+        if read_only {
+            disallow("read-only property", [&changed_signal], errors);
+        }
+        if construct_only.1.is_some() {
+            disallow("construct-only property", [&changed_signal], errors);
+        }
+        // A custom setter stores the value itself, so there is no way to tell if it changed.
+        if matches!(
+            *self.set,
+            Some(
+                PropertyPermission::AllowNoMethod
+                    | PropertyPermission::AllowCustom(_)
+                    | PropertyPermission::AllowCustomDefault
+                    | PropertyPermission::AllowClosure(_)
+            )
+        ) {
+            disallow("property with a custom setter", [&changed_signal], errors);
         }
 
         if matches!(*self.set, Some(PropertyPermission::Deny)) {
-            disallow("read-only property", [&construct, &construct_only], errors);
+            disallow(
+                "read-only property",
+                [&construct, &construct_only, &read_write_construct],
+                errors,
+            );
+        }
+        if matches!(*self.get, Some(PropertyPermission::Deny)) {
+            disallow("write-only property", [&read_write_construct], errors);
         }
 
         if self.borrow.is_some() {
@@ -378,6 +771,68 @@ impl PropertyAttrs {
     }
 }
 
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum AtomicOrdering {
+    SeqCst,
+    AcqRel,
+    Relaxed,
+}
+
+impl FromMeta for AtomicOrdering {
+    fn from_word() -> darling::Result<Self> {
+        Ok(Self::SeqCst)
+    }
+    fn from_string(value: &str) -> darling::Result<Self> {
+        match value {
+            "SeqCst" => Ok(Self::SeqCst),
+            "AcqRel" => Ok(Self::AcqRel),
+            "Relaxed" => Ok(Self::Relaxed),
+            _ => Err(darling::Error::unknown_value(value)),
+        }
+    }
+}
+
+impl AtomicOrdering {
+    fn ordering(&self, load: bool, store: bool) -> TokenStream {
+        let ordering = match (self, load, store) {
+            (Self::SeqCst, _, _) => "SeqCst",
+            (Self::Relaxed, _, _) => "Relaxed",
+            (Self::AcqRel, true, true) => "AcqRel",
+            (Self::AcqRel, true, false) => "Acquire",
+            (Self::AcqRel, _, _) => "Release",
+        };
+        let ordering = format_ident!("{}", ordering);
+        quote! { ::std::sync::atomic::Ordering::#ordering }
+    }
+    fn load(&self, storage: &TokenStream) -> TokenStream {
+        let ordering = self.ordering(true, false);
+        quote! { #storage.load(#ordering) }
+    }
+    fn store(&self, storage: &TokenStream, value: &TokenStream) -> TokenStream {
+        let ordering = self.ordering(false, true);
+        quote! { #storage.store(#value, #ordering) }
+    }
+    fn swap_changed(&self, storage: &TokenStream, value: &syn::Ident) -> TokenStream {
+        let ordering = self.ordering(true, true);
+        quote! { #storage.swap(#value, #ordering) != #value }
+    }
+}
+
+#[derive(Debug)]
+enum OverrideTarget {
+    Infer,
+    Path(syn::Path),
+}
+
+impl FromMeta for OverrideTarget {
+    fn from_word() -> darling::Result<Self> {
+        Ok(Self::Infer)
+    }
+    fn from_string(value: &str) -> darling::Result<Self> {
+        Ok(Self::Path(syn::Path::from_string(value)?))
+    }
+}
+
 #[derive(Debug)]
 struct PropertyStorageAttr {
     storage: syn::Expr,
@@ -426,6 +881,7 @@ pub enum PropertyPermission {
     AllowNoMethod,
     AllowCustomDefault,
     AllowCustom(syn::Path),
+    AllowClosure(Box<syn::ExprClosure>),
 }
 
 impl Default for PropertyPermission {
@@ -448,6 +904,13 @@ impl FromMeta for PropertyPermission {
                 if value == "_" {
                     return Ok(Self::AllowCustomDefault);
                 }
+                if value.trim_start().starts_with('|') {
+                    return Ok(Self::AllowClosure(Box::new(lit.parse()?)));
+                }
+                // `move` closures, without also taking paths like `move_to` for one
+                if let Ok(closure) = lit.parse::<syn::ExprClosure>() {
+                    return Ok(Self::AllowClosure(Box::new(closure)));
+                }
                 Ok(Self::AllowCustom(lit.parse()?))
             }
             syn::Lit::Bool(syn::LitBool { value, .. }) => {
@@ -519,6 +982,85 @@ pub enum PropertyType {
     Object,
 }
 
+/// A `Type => kind` entry of a `#[property_type(...)]` attribute on the module. Fields holding
+/// a `Type`, e.g. `RefCell<Option<Type>>`, build their `ParamSpec` as if marked with
+/// `boxed`, `enum`, `flags` or `object`. Types are compared as written, so the path must be
+/// spelled the same way in the registration and the field. Macros can't see trait impls in other
+/// crates, so the registration has to be spelled out where the macro can read it.
+#[derive(Debug)]
+pub struct PropertyTypeMapping {
+    pub ty: syn::Type,
+    pub kind: PropertyType,
+}
+
+impl syn::parse::Parse for PropertyTypeMapping {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        use syn::ext::IdentExt;
+        let ty = input.parse()?;
+        input.parse::<syn::Token![=>]>()?;
+        let kind = syn::Ident::parse_any(input)?;
+        let kind = match kind.to_string().as_str() {
+            "enum" | "ParamSpecEnum" => PropertyType::Enum,
+            "flags" | "ParamSpecFlags" => PropertyType::Flags,
+            "boxed" | "ParamSpecBoxed" => PropertyType::Boxed,
+            "object" | "ParamSpecObject" => PropertyType::Object,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    kind,
+                    "Expected one of `boxed`, `enum`, `flags`, `object`",
+                ))
+            }
+        };
+        Ok(Self { ty, kind })
+    }
+}
+
+impl PropertyTypeMapping {
+    pub(crate) fn many_from_attrs(attrs: &mut Vec<syn::Attribute>, errors: &Errors) -> Vec<Self> {
+        let attrs = util::extract_attrs(attrs, "property_type").unwrap_or_default();
+        attrs
+            .iter()
+            .flat_map(|attr| {
+                attr.parse_args_with(
+                    syn::punctuated::Punctuated::<Self, syn::Token![,]>::parse_terminated,
+                )
+                .map_err(|e| errors.push_syn(e))
+                .unwrap_or_default()
+            })
+            .collect()
+    }
+    /// Looks through storage wrappers and `Option`, but not other generic types, so a
+    /// registration for `T` doesn't apply to e.g. `Vec<T>`.
+    fn matches(&self, ty: &syn::Type) -> bool {
+        match ty {
+            syn::Type::Path(path) => {
+                if self.ty.to_token_stream().to_string() == path.to_token_stream().to_string() {
+                    return true;
+                }
+                let segment = match path.path.segments.last() {
+                    Some(segment) => segment,
+                    None => return false,
+                };
+                if segment.ident != "Option"
+                    && !STORAGE_WRAPPER_TYPES.iter().any(|t| segment.ident == t)
+                {
+                    return false;
+                }
+                match &segment.arguments {
+                    syn::PathArguments::AngleBracketed(args) => match args.args.first() {
+                        Some(syn::GenericArgument::Type(ty)) => self.matches(ty),
+                        _ => false,
+                    },
+                    _ => false,
+                }
+            }
+            syn::Type::Group(g) => self.matches(&g.elem),
+            syn::Type::Paren(p) => self.matches(&p.elem),
+            _ => false,
+        }
+    }
+}
+
 impl PropertyType {
     fn builder(
         &self,
@@ -609,12 +1151,42 @@ impl std::fmt::Display for PropertyName {
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum PropertyOverride {
     Interface(syn::Path),
+    AnyInterface,
     Class(syn::Path),
 }
 
 impl PropertyOverride {
-    fn pspec(&self, name: &str, glib: &syn::Path) -> TokenStream {
+    fn pspec(&self, name: &str, wrapper_ty: &syn::Type, glib: &syn::Path) -> TokenStream {
         match self {
+            PropertyOverride::AnyInterface => {
+                let iface_ident = syn::Ident::new("iface", Span::mixed_site());
+                let vtable_ident = syn::Ident::new("vtable", Span::mixed_site());
+                let pspec_ident = syn::Ident::new("pspec", Span::mixed_site());
+                quote_spanned! { Span::mixed_site() =>
+                    {
+                        let #pspec_ident = <#wrapper_ty as #glib::StaticType>::static_type()
+                            .interfaces()
+                            .iter()
+                            .find_map(|#iface_ident| unsafe {
+                                let #vtable_ident = #glib::gobject_ffi::g_type_default_interface_ref(
+                                    #glib::translate::IntoGlib::into_glib(*#iface_ident)
+                                );
+                                let #pspec_ident = #glib::translate::from_glib_none::<_, ::std::option::Option<#glib::ParamSpec>>(
+                                    #glib::gobject_ffi::g_object_interface_find_property(
+                                        #vtable_ident,
+                                        #glib::translate::ToGlibPtr::to_glib_none(#name).0,
+                                    )
+                                );
+                                #glib::gobject_ffi::g_type_default_interface_unref(#vtable_ident);
+                                #pspec_ident
+                            })
+                            .unwrap_or_else(|| {
+                                ::std::panic!("Couldn't find a property named `{}` to override", #name)
+                            });
+                        #glib::ParamSpecOverride::new(#name, &#pspec_ident)
+                    }
+                }
+            }
             PropertyOverride::Interface(target) => quote_spanned! { target.span() =>
                 #glib::ParamSpecOverride::for_interface::<#target>(#name)
             },
@@ -626,7 +1198,7 @@ impl PropertyOverride {
 }
 
 pub(crate) struct Properties {
-    pub(crate) final_type: Option<syn::Ident>,
+    pub(crate) final_type: Option<syn::Path>,
     pub(crate) base: TypeBase,
     pub(crate) properties: Vec<Property>,
     pub(crate) fields: syn::Fields,
@@ -661,12 +1233,14 @@ impl Properties {
                 Default::default()
             }
         };
-        if base.is_none() {
+        // Inside a class or interface module the macro already knows the type and its base
+        if base.is_some() {
             if let Some(final_type) = &final_type {
                 errors.push_spanned(final_type, "`final_type` not allowed here");
             }
-        } else if interface.is_some() {
-            errors.push(interface.span(), "`interface` not allowed here");
+            if interface.is_some() {
+                errors.push(interface.span(), "`interface` not allowed here");
+            }
         }
         let pod = pod.is_some();
         let base = base.unwrap_or_else(|| {
@@ -738,12 +1312,24 @@ pub struct Property {
     pub get: PropertyPermission,
     pub set: PropertyPermission,
     pub borrow: bool,
+    pub snapshot: bool,
+    pub downcast: Option<syn::Type>,
+    pub accessors: bool,
+    pub value_accessor: bool,
+    pub bind: bool,
+    pub atomic: Option<AtomicOrdering>,
     pub notify: bool,
     pub connect_notify: bool,
+    pub changed_signal: Option<syn::LitStr>,
+    pub vis: Option<syn::Visibility>,
     pub nick: Option<String>,
     pub blurb: Option<String>,
+    pub default: Option<syn::Expr>,
+    pub parent_default: Option<syn::Expr>,
+    pub construct_after: Option<syn::LitStr>,
     pub buildable_defaults: Vec<syn::Expr>,
     pub buildable_props: Vec<(syn::Ident, syn::Expr)>,
+    pub buildable_closure: Option<syn::ExprClosure>,
     pub flags: PropertyFlags,
 }
 
@@ -772,30 +1358,163 @@ impl Property {
             get: (*attrs.get).take().unwrap_or_default(),
             set: (*attrs.set).take().unwrap_or_default(),
             borrow: attrs.borrow.is_some(),
+            snapshot: attrs.snapshot.is_some(),
+            downcast: attrs.downcast.take(),
+            accessors: attrs.no_accessors.is_none(),
+            value_accessor: attrs.value_accessor.is_some(),
+            bind: attrs.bind.is_some(),
+            atomic: attrs.atomic.as_deref().copied(),
             notify: attrs.notify.unwrap_or(true),
             connect_notify: attrs.connect_notify.unwrap_or(true),
+            changed_signal: attrs.changed_signal.take(),
+            vis: attrs.vis_.take(),
             nick: attrs.nick.take().map(|n| n.value()),
             blurb: attrs.blurb.take().map(|b| b.value()),
+            default: attrs.default.take().map(|d| d.0),
+            parent_default: attrs.parent_default.take().map(|d| d.0),
+            construct_after: attrs.construct_after.take(),
             buildable_defaults: attrs
                 .builder_defaults
                 .map(|d| d.elems.into_iter().collect())
                 .unwrap_or_default(),
-            buildable_props: std::mem::take(&mut *attrs.builder)
+            buildable_closure: attrs.builder.closure.take(),
+            buildable_props: std::mem::take(&mut attrs.builder.props)
                 .into_iter()
                 .map(|(i, e)| (i, e.0))
+                .chain(
+                    attrs
+                        .minimum
+                        .take()
+                        .map(|m| (format_ident!("minimum"), m.0)),
+                )
+                .chain(
+                    attrs
+                        .maximum
+                        .take()
+                        .map(|m| (format_ident!("maximum"), m.0)),
+                )
                 .collect(),
             flags,
         })
     }
-    pub(crate) fn definition(&self, go: &syn::Path) -> TokenStream {
+    /// Collects read-only properties declared as `#[property(get)] fn area(&self) -> f64` on
+    /// methods of the subclass. Each one becomes a computed property named after the method, so
+    /// the generated `property` calls it like any other computed getter.
+    pub(crate) fn many_from_items(
+        items: &mut [syn::ImplItem],
+        base: TypeBase,
+        mode: TypeMode,
+        errors: &Errors,
+    ) -> Vec<Self> {
+        let mut properties = Vec::new();
+        for item in items {
+            let method = match item {
+                syn::ImplItem::Method(method) => method,
+                _ => continue,
+            };
+            let attrs = match util::extract_attrs(&mut method.attrs, "property") {
+                Some(attrs) => attrs,
+                None => continue,
+            };
+            if base != TypeBase::Class || mode != TypeMode::Subclass {
+                errors.push_spanned(
+                    &method.sig,
+                    "Property methods are only allowed on the subclass of a class",
+                );
+                continue;
+            }
+            let sig = &method.sig;
+            let ty = match &sig.output {
+                syn::ReturnType::Type(_, ty)
+                    if sig.inputs.len() == 1
+                        && sig.generics.params.is_empty()
+                        && sig.asyncness.is_none()
+                        && matches!(
+                            sig.inputs.first(),
+                            Some(syn::FnArg::Receiver(syn::Receiver {
+                                reference: Some(_),
+                                mutability: None,
+                                ..
+                            }))
+                        ) =>
+                {
+                    ty
+                }
+                _ => {
+                    errors.push_spanned(
+                        sig,
+                        "Property method must have the signature `fn name(&self) -> T`",
+                    );
+                    continue;
+                }
+            };
+            let ident = &sig.ident;
+            let docs = method.attrs.iter().filter(|a| a.path.is_ident("doc"));
+            let fields: syn::FieldsNamed = parse_quote_spanned! { ident.span() => {
+                #(#docs)*
+                #(#attrs)*
+                #ident: ::std::marker::PhantomData<#ty>
+            } };
+            let field = fields.named.into_iter().next().unwrap();
+            let mut prop_attrs = match PropertyAttrs::from_field(&field) {
+                Ok(attrs) => attrs,
+                Err(e) => {
+                    errors.push_darling(e);
+                    continue;
+                }
+            };
+            if !matches!(*prop_attrs.get, Some(PropertyPermission::Allow)) {
+                errors.push_spanned(&attrs[0], "Property method requires a plain `get`");
+                continue;
+            }
+            if (*prop_attrs.set).is_some() {
+                errors.push(prop_attrs.set.span(), "Property methods are read-only");
+                continue;
+            }
+            if prop_attrs.computed.is_some() {
+                errors.push(
+                    prop_attrs.computed.span(),
+                    "Property methods are always computed",
+                );
+            }
+            prop_attrs.computed = SpannedValue::new(Flag::present(), prop_attrs.get.span());
+            properties.extend(Self::new(prop_attrs, &field, 0, false, base, errors));
+        }
+        properties
+    }
+    pub(crate) fn definition(&self, wrapper_ty: &syn::Type, go: &syn::Path) -> TokenStream {
         let glib: syn::Path = parse_quote! { #go::glib };
         let name = self.name.to_string();
-        if let Some(override_) = &self.override_ {
-            return override_.pspec(&name, &glib);
-        }
-        let nick = self.nick.clone().unwrap_or_else(|| name.clone());
-        let blurb = self.blurb.clone().unwrap_or_else(|| name.clone());
-        let flags = self.flags.tokens(&glib);
+        let (parent, nick, blurb, flags) = match &self.override_ {
+            Some(override_) => {
+                let pspec = override_.pspec(&name, wrapper_ty, &glib);
+                if self.parent_default.is_none() {
+                    return pspec;
+                }
+                // `ParamSpecOverride` always reports the default of the parent pspec, so install
+                // a new pspec with the parent's nick, blurb and flags instead.
+                let parent_ident = syn::Ident::new("parent", Span::mixed_site());
+                (
+                    Some(quote_spanned! { Span::mixed_site() =>
+                        let #parent_ident = #glib::ParamSpec::redirect_target(&#pspec)
+                            .expect("overridden property without a redirect target");
+                    }),
+                    quote! { #parent_ident.nick() },
+                    quote! { #parent_ident.blurb() },
+                    quote! { #parent_ident.flags() },
+                )
+            }
+            None => {
+                let nick = self.nick.clone().unwrap_or_else(|| name.clone());
+                let blurb = self.blurb.clone().unwrap_or_else(|| name.clone());
+                (
+                    None,
+                    quote! { #nick },
+                    quote! { #blurb },
+                    self.flags.tokens(&glib),
+                )
+            }
+        };
         let ty = self.store_type(go);
         let props = self
             .buildable_props
@@ -804,14 +1523,78 @@ impl Property {
         let builder = self
             .special_type
             .builder(&name, &self.buildable_defaults, &ty, go);
-        quote_spanned! { self.span() =>
+        let default = self.default_value(&ty, go);
+        let builder = quote_spanned! { self.span() =>
             #builder
             #(#props)*
+            #default
             .nick(#nick)
             .blurb(#blurb)
             .flags(#flags)
-            .build()
-        }
+        };
+        let builder = match &self.buildable_closure {
+            Some(closure) => quote_spanned! { Span::mixed_site() =>
+                {
+                    fn apply<B, R>(builder: B, f: impl FnOnce(B) -> R) -> R {
+                        f(builder)
+                    }
+                    apply(#builder, #closure)
+                }
+            },
+            None => builder,
+        };
+        quote_spanned! { self.span() => { #parent #builder.build() } }
+    }
+    /// The `default_value` builder call from `default`. Enum and flags pspecs take the raw
+    /// value, and enums without any default use their `Default` impl if they have one.
+    fn default_value(&self, ty: &syn::Type, go: &syn::Path) -> Option<TokenStream> {
+        let glib: syn::Path = parse_quote! { #go::glib };
+        let default = match self.default.as_ref().or(self.parent_default.as_ref()) {
+            Some(default) => default,
+            None => {
+                let has_default = !self.buildable_defaults.is_empty()
+                    || self
+                        .buildable_props
+                        .iter()
+                        .any(|(i, _)| i == "default_value");
+                if self.special_type != PropertyType::Enum || has_default {
+                    return None;
+                }
+                return Some(quote_spanned! { Span::mixed_site() =>
+                    .default_value({
+                        #[allow(unused_imports)]
+                        use #go::{EnumDefaultValue as _, EnumFallbackValue as _};
+                        (&#go::EnumDefault::<#ty>(::std::marker::PhantomData)).enum_default_value()
+                    })
+                });
+            }
+        };
+        let value = match (&self.special_type, default) {
+            (PropertyType::Enum | PropertyType::Flags, _) => quote_spanned! { default.span() =>
+                #glib::translate::IntoGlib::into_glib(#default)
+            },
+            (
+                _,
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(_),
+                    ..
+                }),
+            ) => quote_spanned! { default.span() => ::std::option::Option::Some(#default) },
+            _ => quote! { #default },
+        };
+        Some(quote_spanned! { default.span() => .default_value(#value) })
+    }
+    /// Whether the pspec gets a default value from the attributes, as opposed to the zero value
+    /// of its type.
+    pub fn has_default(&self) -> bool {
+        self.default.is_some()
+            || self.parent_default.is_some()
+            || self.special_type == PropertyType::Enum
+            || !self.buildable_defaults.is_empty()
+            || self
+                .buildable_props
+                .iter()
+                .any(|(i, _)| i == "default_value")
     }
     pub fn store_type(&self, go: &syn::Path) -> syn::Type {
         let ty = &self.field.ty;
@@ -844,6 +1627,9 @@ impl Property {
         } else {
             quote_spanned! { self.span() => #self_ident }
         };
+        self.field_storage_on(recv)
+    }
+    fn field_storage_on(&self, recv: TokenStream) -> (TokenStream, Option<&syn::Expr>) {
         let storage = match &self.storage {
             PropertyStorage::NamedField(field) => quote_spanned! { field.span() => #recv.#field },
             PropertyStorage::UnnamedField(index) => quote_spanned! { self.span() => #recv.#index },
@@ -866,6 +1652,40 @@ impl Property {
         };
         (storage, None)
     }
+    /// Copies the pspec default of a property using `default` or `parent_default` into its
+    /// storage from `instance_init`, so the initial value matches what the pspec reports
+    /// regardless of the field's `Default` impl.
+    pub(crate) fn default_init(
+        &self,
+        imp: &syn::Ident,
+        pspec: &TokenStream,
+        go: &syn::Path,
+    ) -> Option<TokenStream> {
+        let default = self.default.as_ref().or(self.parent_default.as_ref())?;
+        if self.is_abstract() || matches!(self.storage, PropertyStorage::Computed) {
+            return None;
+        }
+        let (storage, field) = self.field_storage_on(quote! { #imp });
+        let value = quote_spanned! { default.span() =>
+            #go::glib::ParamSpec::default_value(#pspec)
+        };
+        Some(if let Some(field) = field {
+            quote_spanned! { default.span() =>
+                #go::ParamStoreBorrowMut::borrow_mut(&#storage).#field =
+                    #value.get().expect("invalid default value for property");
+            }
+        } else if let Some(atomic) = &self.atomic {
+            let store = atomic.store(
+                &storage,
+                &quote! { #value.get().expect("invalid default value for property") },
+            );
+            quote_spanned! { default.span() => #store; }
+        } else {
+            quote_spanned! { default.span() =>
+                #go::ParamStoreWrite::set_value(&#storage, #value);
+            }
+        })
+    }
     fn is_inherited(&self) -> bool {
         self.override_.is_some()
     }
@@ -952,6 +1772,38 @@ impl Property {
                 }),
         )
     }
+    /// Calls a `get = "|this| ..."` or `set = "|this, value| ..."` closure from the generated
+    /// `property`/`set_property` branch, with `this` being the subclass struct. The closure is
+    /// coerced to a `fn` pointer first so its signature is checked against the property type.
+    #[inline]
+    fn closure_call(&self, set_ty: Option<&syn::Type>, go: &syn::Path) -> Option<TokenStream> {
+        let perm = match set_ty.is_some() {
+            true => &self.set,
+            false => &self.get,
+        };
+        let closure = match perm {
+            PropertyPermission::AllowClosure(closure) => closure,
+            _ => return None,
+        };
+        let func_ident = syn::Ident::new("func", Span::mixed_site());
+        let self_ident = syn::Ident::new("self", Span::mixed_site());
+        Some(match set_ty {
+            Some(ty) => {
+                let value_ident = syn::Ident::new("value", Span::mixed_site());
+                quote_spanned! { closure.span() => {
+                    let #func_ident: fn(&Self, #ty) = #closure;
+                    #func_ident(#self_ident, #value_ident.get::<#ty>().unwrap())
+                } }
+            }
+            None => {
+                let ty = self.store_read_type(go);
+                quote_spanned! { closure.span() => {
+                    let #func_ident: fn(&Self) -> #ty = #closure;
+                    #func_ident(#self_ident)
+                } }
+            }
+        })
+    }
     #[inline]
     pub fn getter_name(&self) -> syn::Ident {
         let mut name = self.name.field_name().to_string();
@@ -971,7 +1823,10 @@ impl Property {
         (self.get.is_allowed() && !self.is_abstract()).then(|| {
             let glib: syn::Path = parse_quote! { #go::glib };
             let cmp = self.pspec_cmp(index);
-            let body = if let Some(call) = self.custom_call(None, method, &glib) {
+            let call = self
+                .closure_call(None, go)
+                .or_else(|| self.custom_call(None, method, &glib));
+            let body = if let Some(call) = call {
                 quote_spanned! { self.span() => #glib::ToValue::to_value(&#call) }
             } else {
                 let (storage, field) = self.field_storage(None, go);
@@ -982,7 +1837,12 @@ impl Property {
                         )
                     }
                 } else {
-                    quote_spanned! { self.span() => #go::ParamStoreRead::get_value(&#storage) }
+                    if let Some(atomic) = &self.atomic {
+                        let load = atomic.load(&storage);
+                        quote_spanned! { self.span() => #glib::ToValue::to_value(&#load) }
+                    } else {
+                        quote_spanned! { self.span() => #go::ParamStoreRead::get_value(&#storage) }
+                    }
                 }
             };
             quote_spanned! { self.span() =>
@@ -992,20 +1852,53 @@ impl Property {
             }
         })
     }
+    fn doc_attrs(&self, style: syn::AttrStyle) -> Vec<syn::Attribute> {
+        self.field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("doc"))
+            .map(|attr| syn::Attribute {
+                style,
+                ..attr.clone()
+            })
+            .collect()
+    }
+    #[inline]
+    pub fn has_getter(&self) -> bool {
+        self.accessors
+            && !self.is_inherited()
+            && matches!(
+                self.get,
+                PropertyPermission::Allow | PropertyPermission::AllowClosure(_)
+            )
+    }
     fn getter_prototype(&self, go: &syn::Path) -> Option<TokenStream> {
-        (!self.is_inherited() && matches!(self.get, PropertyPermission::Allow)).then(|| {
+        self.has_getter().then(|| {
             let method_name = self.getter_name();
-            let ty = self.store_read_type(go);
+            let ty = if self.snapshot {
+                self.store_borrow_type(go)
+            } else {
+                self.downcast
+                    .clone()
+                    .unwrap_or_else(|| self.store_read_type(go))
+            };
             quote_spanned! { Span::mixed_site() => fn #method_name(&self) -> #ty }
         })
     }
     fn getter_definition(&self, object_type: &syn::Type, go: &syn::Path) -> Option<TokenStream> {
         self.getter_prototype(go).map(|proto| {
-            let body = if self.is_abstract() {
+            let by_name = matches!(self.get, PropertyPermission::AllowClosure(_));
+            let body = if self.is_abstract() || by_name {
                 let name = self.name.to_string();
                 let self_ident = syn::Ident::new("self", Span::mixed_site());
-                quote_spanned! { self.span() =>
-                    <Self as #go::glib::object::ObjectExt>::property(#self_ident, #name)
+                if self.downcast.is_some() {
+                    quote_spanned! { self.span() =>
+                        <Self as #go::glib::object::ObjectExt>::property_value(#self_ident, #name)
+                    }
+                } else {
+                    quote_spanned! { self.span() =>
+                        <Self as #go::glib::object::ObjectExt>::property(#self_ident, #name)
+                    }
                 }
             } else {
                 let (storage, field) = self.field_storage(Some(object_type), go);
@@ -1016,17 +1909,140 @@ impl Property {
                         )
                     }
                 } else {
-                    quote_spanned! { self.span() => #go::ParamStoreRead::get_owned(&#storage) }
+                    if let Some(atomic) = &self.atomic {
+                        atomic.load(&storage)
+                    } else if self.snapshot {
+                        quote_spanned! { self.span() => #go::ParamStoreBorrow::borrow(&#storage) }
+                    } else {
+                        quote_spanned! { self.span() => #go::ParamStoreRead::get_owned(&#storage) }
+                    }
                 }
             };
+            let body = if let Some(downcast) = &self.downcast {
+                self.downcast_value(&body, downcast, go)
+            } else {
+                body
+            };
+            let docs = self.doc_attrs(syn::AttrStyle::Inner(Default::default()));
             quote_spanned! { self.span() =>
                 #proto {
+                    #(#docs)*
                     #![inline]
                     #body
                 }
             }
         })
     }
+    /// `foo_value` is generated alongside the typed `foo` getter. It reads the property through
+    /// `ObjectExt::property_value`, so the result is the same `Value` that bindings and
+    /// `property_value("foo")` see, without a round trip through the Rust type.
+    fn value_getter_prototype(&self, glib: &syn::Path) -> Option<TokenStream> {
+        (self.value_accessor && !self.is_inherited()).then(|| {
+            let method_name = format_ident!("{}_value", self.name.field_name(), span = self.span());
+            quote_spanned! { Span::mixed_site() => fn #method_name(&self) -> #glib::Value }
+        })
+    }
+    fn value_getter_definition(&self, glib: &syn::Path) -> Option<TokenStream> {
+        self.value_getter_prototype(glib).map(|proto| {
+            let name = self.name.to_string();
+            quote_spanned! { Span::mixed_site() =>
+                #proto {
+                    #![inline]
+                    <Self as #glib::object::ObjectExt>::property_value(self, #name)
+                }
+            }
+        })
+    }
+    /// `bind_foo_full` wraps `g_object_bind_property_full` with `foo` as the source property.
+    /// Both transforms take the `Binding` and the value being copied, and return the value to
+    /// store on the other side, or `None` to skip the update. `transform_from` only runs for
+    /// `BindingFlags::BIDIRECTIONAL` bindings.
+    fn bind_prototype(&self, glib: &syn::Path) -> Option<TokenStream> {
+        (self.bind && !self.is_inherited()).then(|| {
+            let method_name =
+                format_ident!("bind_{}_full", self.name.field_name(), span = self.span());
+            quote_spanned! { Span::mixed_site() =>
+                fn #method_name<T, F, G>(
+                    &self,
+                    target: &T,
+                    target_property: &str,
+                    flags: #glib::BindingFlags,
+                    transform_to: F,
+                    transform_from: G,
+                ) -> #glib::Binding
+                where
+                    T: #glib::ObjectType,
+                    F: Fn(&#glib::Binding, &#glib::Value) -> ::std::option::Option<#glib::Value>
+                        + Send
+                        + Sync
+                        + 'static,
+                    G: Fn(&#glib::Binding, &#glib::Value) -> ::std::option::Option<#glib::Value>
+                        + Send
+                        + Sync
+                        + 'static
+            }
+        })
+    }
+    fn bind_definition(&self, glib: &syn::Path) -> Option<TokenStream> {
+        self.bind_prototype(glib).map(|proto| {
+            let name = self.name.to_string();
+            quote_spanned! { Span::mixed_site() =>
+                #proto {
+                    #![inline]
+                    <Self as #glib::object::ObjectExt>::bind_property(
+                        self,
+                        #name,
+                        target,
+                        target_property,
+                    )
+                    .flags(flags)
+                    .transform_to(transform_to)
+                    .transform_from(transform_from)
+                    .build()
+                }
+            }
+        })
+    }
+    fn downcast_value(
+        &self,
+        value: &TokenStream,
+        downcast: &syn::Type,
+        go: &syn::Path,
+    ) -> TokenStream {
+        let glib: syn::Path = parse_quote! { #go::glib };
+        let value = if self.is_abstract() {
+            value.clone()
+        } else {
+            quote_spanned! { self.span() => #glib::ToValue::to_value(&#value) }
+        };
+        let nullable = match downcast {
+            syn::Type::Path(p) => p
+                .path
+                .segments
+                .last()
+                .map(|s| s.ident == "Option")
+                .unwrap_or(false),
+            _ => false,
+        };
+        if nullable {
+            quote_spanned! { downcast.span() =>
+                #glib::Value::get::<#downcast>(&#value).unwrap_or(::std::option::Option::None)
+            }
+        } else {
+            let name = self.name.to_string();
+            let error_ident = syn::Ident::new("e", Span::mixed_site());
+            quote_spanned! { downcast.span() =>
+                #glib::Value::get::<#downcast>(&#value).unwrap_or_else(|#error_ident| {
+                    ::std::panic!(
+                        "Property `{}` does not hold a `{}`: {}",
+                        #name,
+                        ::std::any::type_name::<#downcast>(),
+                        #error_ident,
+                    )
+                })
+            }
+        }
+    }
     #[inline]
     fn borrow_name(&self) -> syn::Ident {
         format_ident!("borrow_{}", self.name.field_name(), span = self.span())
@@ -1099,8 +2115,15 @@ impl Property {
                         }
                     }
                 } else {
+                    let changed = if let Some(atomic) = &self.atomic {
+                        atomic.swap_changed(&storage, &value_ident)
+                    } else {
+                        quote_spanned! { self.span() =>
+                            #go::ParamStoreWriteChanged::set_owned_checked(&#storage, #value_ident)
+                        }
+                    };
                     quote_spanned! { self.span() =>
-                        if #go::ParamStoreWriteChanged::set_owned_checked(&#storage, #value_ident) {
+                        if #changed {
                             #notify
                         }
                     }
@@ -1111,6 +2134,9 @@ impl Property {
             quote_spanned! { self.span() =>
                 #go::ParamStoreBorrowMut::borrow_mut(&#storage).#field = #value_ident;
             }
+        } else if let Some(atomic) = &self.atomic {
+            let store = atomic.store(&storage, &value_ident.to_token_stream());
+            quote_spanned! { self.span() => #store; }
         } else {
             quote_spanned! { self.span() =>
                 #go::ParamStoreWrite::set_owned(&#storage, #value_ident);
@@ -1128,19 +2154,29 @@ impl Property {
             let cmp = self.pspec_cmp(index);
             let ty = self.store_write_type(go);
             let value_ident = syn::Ident::new("value", Span::mixed_site());
-            let body = if let Some(call) = self.custom_call(Some(&ty), method, &glib) {
+            let obj_ident = syn::Ident::new("obj", Span::mixed_site());
+            let changed = self.changed_signal_emit(&quote! { #obj_ident }, &glib);
+            let call = self
+                .closure_call(Some(&ty), go)
+                .or_else(|| self.custom_call(Some(&ty), method, &glib));
+            let explicit_notify =
+                self.is_set_inline() && self.flags.contains(PropertyFlags::EXPLICIT_NOTIFY);
+            let body = if let Some(call) = call {
                 quote! { #call; }
-            } else if self.is_set_inline() {
-                let obj_ident = syn::Ident::new("obj", Span::mixed_site());
+            } else if self.is_set_inline() || changed.is_some() {
+                // Same as the inline setter, so `changed_signal` is only emitted on an actual
+                // change no matter how the property is set.
                 let pspec_ident = syn::Ident::new("pspec", Span::mixed_site());
                 let body = self.inline_set_impl(
                     None,
-                    self.flags.contains(PropertyFlags::EXPLICIT_NOTIFY)
-                    .then(|| || quote_spanned! { self.span() =>
-                        <<Self as #glib::subclass::types::ObjectSubclass>::Type as #glib::object::ObjectExt>::notify_by_pspec(
-                            #obj_ident,
-                            #pspec_ident
-                        );
+                    (explicit_notify || changed.is_some()).then(|| {
+                        let notify = explicit_notify.then(|| quote_spanned! { self.span() =>
+                            <<Self as #glib::subclass::types::ObjectSubclass>::Type as #glib::object::ObjectExt>::notify_by_pspec(
+                                #obj_ident,
+                                #pspec_ident
+                            );
+                        });
+                        move || quote! { #notify #changed }
                     }),
                     go
                 );
@@ -1154,6 +2190,9 @@ impl Property {
                     quote_spanned! { self.span() =>
                         #go::ParamStoreBorrowMut::borrow_mut(&#storage).#field = #value_ident.get().unwrap();
                     }
+                } else if let Some(atomic) = &self.atomic {
+                    let store = atomic.store(&storage, &quote! { #value_ident.get().unwrap() });
+                    quote_spanned! { self.span() => #store; }
                 } else {
                     quote_spanned! { self.span() =>
                         #go::ParamStoreWrite::set_value(&#storage, &#value_ident);
@@ -1168,16 +2207,23 @@ impl Property {
             }
         })
     }
+    fn changed_signal_emit(&self, obj: &TokenStream, glib: &syn::Path) -> Option<TokenStream> {
+        self.changed_signal.as_ref().map(|signal| {
+            quote_spanned! { signal.span() =>
+                #glib::object::ObjectExt::emit_by_name::<()>(#obj, #signal, &[]);
+            }
+        })
+    }
     fn setter_prototype(&self, go: &syn::Path) -> Option<TokenStream> {
         let construct_only = self.flags.contains(PropertyFlags::CONSTRUCT_ONLY);
         let allowed = match &self.set {
             PropertyPermission::Allow => true,
-            PropertyPermission::AllowCustom(_) | PropertyPermission::AllowCustomDefault => {
-                !self.is_set_inline()
-            }
+            PropertyPermission::AllowCustom(_)
+            | PropertyPermission::AllowCustomDefault
+            | PropertyPermission::AllowClosure(_) => !self.is_set_inline(),
             _ => false,
         };
-        (allowed && !construct_only && !self.is_inherited()).then(|| {
+        (allowed && self.accessors && !construct_only && !self.is_inherited()).then(|| {
             let method_name = self.setter_name();
             let ty = self.store_write_type(go);
             quote_spanned! { Span::mixed_site() => fn #method_name(&self, value: #ty) }
@@ -1194,6 +2240,8 @@ impl Property {
             let self_ident = syn::Ident::new("self", Span::mixed_site());
             let value_ident = syn::Ident::new("value", Span::mixed_site());
             let body = if !self.is_abstract() && self.is_set_inline() {
+                let glib: syn::Path = parse_quote! { #go::glib };
+                let changed = self.changed_signal_emit(&quote! { #self_ident }, &glib);
                 self.inline_set_impl(
                     Some(object_type),
                     Some(|| {
@@ -1202,6 +2250,7 @@ impl Property {
                                 #self_ident,
                                 &#properties_path()[#index]
                             );
+                            #changed
                         }
                     }),
                     go,
@@ -1212,8 +2261,10 @@ impl Property {
                     <Self as #go::glib::object::ObjectExt>::set_property(#self_ident, #name, #value_ident);
                 }
             };
+            let docs = self.doc_attrs(syn::AttrStyle::Inner(Default::default()));
             quote_spanned! { self.span() =>
                 #proto {
+                    #(#docs)*
                     #![inline]
                     #body
                 }
@@ -1296,15 +2347,32 @@ impl Property {
                 }
             })
     }
+    /// Applies the first matching module-level `property_type` registration. Properties that
+    /// already chose a kind, pass `builder_defaults` or override a parent property are left alone.
+    pub(crate) fn apply_type_mappings(&mut self, mappings: &[PropertyTypeMapping]) {
+        if self.special_type != PropertyType::Unspecified
+            || self.override_.is_some()
+            || !self.buildable_defaults.is_empty()
+        {
+            return;
+        }
+        if let Some(mapping) = mappings.iter().find(|m| m.matches(&self.field.ty)) {
+            self.special_type = mapping.kind;
+        }
+    }
     pub(crate) fn method_prototypes(
         &self,
         concurrency: Concurrency,
         go: &syn::Path,
     ) -> Vec<TokenStream> {
         let glib: syn::Path = parse_quote! { #go::glib };
+        let docs = self.doc_attrs(syn::AttrStyle::Outer);
+        let documented = |proto: TokenStream| quote! { #(#docs)* #proto };
         [
-            self.setter_prototype(go),
-            self.getter_prototype(go),
+            self.setter_prototype(go).map(&documented),
+            self.getter_prototype(go).map(&documented),
+            self.value_getter_prototype(&glib),
+            self.bind_prototype(&glib),
             self.borrow_prototype(go),
             self.notify_prototype(),
             self.connect_prototype(concurrency, false, &glib),
@@ -1328,6 +2396,8 @@ impl Property {
         [
             self.setter_definition(index, ty, properties_path, go),
             self.getter_definition(ty, go),
+            self.value_getter_definition(&glib),
+            self.bind_definition(&glib),
             self.borrow_definition(ty, go),
             self.notify_definition(index, properties_path, &glib),
             self.connect_definition(concurrency, false, &glib),
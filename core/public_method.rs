@@ -4,7 +4,10 @@ use crate::{
     util::{self, Errors},
     TypeBase, TypeMode,
 };
-use darling::{util::Flag, FromAttributes};
+use darling::{
+    util::{Flag, SpannedValue},
+    FromAttributes, FromMeta,
+};
 use heck::ToKebabCase;
 use proc_macro2::{Span, TokenStream};
 use quote::{quote, quote_spanned};
@@ -27,14 +30,112 @@ pub enum ConstructorType {
         sig: Box<syn::Signature>,
         renames: HashMap<usize, String>,
         default: bool,
-        fallible: bool,
+        on_error: ConstructorOnError,
     },
     Custom {
         default: bool,
-        fallible: bool,
+        on_error: ConstructorOnError,
     },
 }
 
+/// Receivers that can be forwarded from the generated method to the user's method.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum ReceiverType {
+    Ref,
+    Value,
+    PinRef,
+}
+
+impl ReceiverType {
+    fn from_arg(arg: &syn::FnArg) -> Option<Self> {
+        let ty = match arg {
+            syn::FnArg::Receiver(syn::Receiver {
+                reference: Some(_),
+                mutability: None,
+                ..
+            }) => return Some(Self::Ref),
+            syn::FnArg::Receiver(syn::Receiver {
+                reference: None, ..
+            }) => return Some(Self::Value),
+            syn::FnArg::Receiver(_) => return None,
+            syn::FnArg::Typed(pat) => &*pat.ty,
+        };
+        let is_self = |ty: &syn::Type| matches!(ty, syn::Type::Path(p) if p.path.is_ident("Self"));
+        let is_self_ref = |ty: &syn::Type| match ty {
+            syn::Type::Reference(r) => r.mutability.is_none() && is_self(&r.elem),
+            _ => false,
+        };
+        if is_self(ty) {
+            return Some(Self::Value);
+        }
+        if is_self_ref(ty) {
+            return Some(Self::Ref);
+        }
+        let segment = match ty {
+            syn::Type::Path(p) if p.qself.is_none() => p.path.segments.last()?,
+            _ => return None,
+        };
+        match &segment.arguments {
+            syn::PathArguments::AngleBracketed(args)
+                if segment.ident == "Pin" && args.args.len() == 1 =>
+            {
+                match args.args.first() {
+                    Some(syn::GenericArgument::Type(ty)) if is_self_ref(ty) => Some(Self::PinRef),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum ConstructorOnError {
+    Propagate,
+    Unwrap,
+    Expect(Option<String>),
+}
+
+impl FromMeta for ConstructorOnError {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        match value {
+            "propagate" => Ok(Self::Propagate),
+            "unwrap" => Ok(Self::Unwrap),
+            "expect" => Ok(Self::Expect(None)),
+            _ => Err(darling::Error::unknown_value(value)),
+        }
+    }
+    fn from_list(items: &[syn::NestedMeta]) -> darling::Result<Self> {
+        match items {
+            [syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                path,
+                lit: syn::Lit::Str(message),
+                ..
+            }))] if path.is_ident("expect") => Ok(Self::Expect(Some(message.value()))),
+            _ => Err(darling::Error::unsupported_format(
+                "list other than `expect = \"...\"`",
+            )),
+        }
+    }
+}
+
+impl ConstructorOnError {
+    pub fn handler(&self, wrapper_ty: &syn::Type, glib: &syn::Path) -> Option<TokenStream> {
+        match self {
+            Self::Propagate => None,
+            Self::Unwrap => Some(quote! { .unwrap() }),
+            Self::Expect(Some(message)) => Some(quote! { .expect(#message) }),
+            Self::Expect(None) => Some(quote! { .unwrap_or_else(|e| {
+                ::std::panic!(
+                    "Failed to construct {}: {:?}",
+                    <#wrapper_ty as #glib::StaticType>::static_type().name(),
+                    e,
+                );
+            }) }),
+        }
+    }
+}
+
 impl ConstructorType {
     pub fn is_default(&self) -> bool {
         match self {
@@ -42,18 +143,22 @@ impl ConstructorType {
             Self::Custom { default, .. } => *default,
         }
     }
-    pub fn fallible(&self) -> bool {
+    pub fn on_error(&self) -> &ConstructorOnError {
         match self {
-            Self::Auto { fallible, .. } => *fallible,
-            Self::Custom { fallible, .. } => *fallible,
+            Self::Auto { on_error, .. } => on_error,
+            Self::Custom { on_error, .. } => on_error,
         }
     }
+    pub fn fallible(&self) -> bool {
+        *self.on_error() == ConstructorOnError::Propagate
+    }
 }
 
 #[derive(Default, FromAttributes)]
 #[darling(default, attributes(public))]
 struct PublicMethodAttrs {
     name: Option<syn::Ident>,
+    delegate: Option<syn::Expr>,
 }
 
 #[derive(Default, FromAttributes)]
@@ -61,7 +166,8 @@ struct PublicMethodAttrs {
 struct ConstructorAttrs {
     name: Option<syn::Ident>,
     default: Flag,
-    infallible: Flag,
+    infallible: SpannedValue<Flag>,
+    on_error: Option<SpannedValue<ConstructorOnError>>,
 }
 
 impl PublicMethod {
@@ -109,13 +215,53 @@ impl PublicMethod {
                 let attrs = util::parse_attributes::<ConstructorAttrs>(&attrs, errors);
                 name = attrs.name;
                 let default = attrs.default.is_some();
-                let fallible = attrs.infallible.is_none();
+                let on_error_span = attrs.on_error.as_ref().map(|o| o.span());
+                {
+                    use crate::validations::*;
+                    let infallible = ("infallible", check_flag(&attrs.infallible));
+                    let on_error = ("on_error", on_error_span);
+                    only_one([&infallible, &on_error], errors);
+                }
+                let on_error = match attrs.on_error {
+                    Some(on_error) => (*on_error).clone(),
+                    None if attrs.infallible.is_some() => ConstructorOnError::Expect(None),
+                    None => ConstructorOnError::Propagate,
+                };
+                if let Some(span) = on_error_span {
+                    if on_error == ConstructorOnError::Propagate && !returns_result(&method.sig) {
+                        errors.push(
+                            span,
+                            "`on_error = \"propagate\"` requires a `Result` return type",
+                        );
+                    }
+                }
                 if let Some(recv) = method.sig.receiver() {
                     errors.push_spanned(recv, "`self` not allowed on constructor");
                 }
                 if matches!(&method.sig.output, syn::ReturnType::Default) {
                     errors.push_spanned(&method.sig, "Constructor must have a return type");
                 }
+                // Generated bodies and `Default` impls apply `on_error` to the result, so a
+                // mismatched return type would otherwise only fail inside generated code.
+                if let syn::ReturnType::Type(_, ty) = &method.sig.output {
+                    if method.block.stmts.is_empty() || default {
+                        let result = returns_result(&method.sig);
+                        if on_error == ConstructorOnError::Propagate
+                            && !result
+                            && on_error_span.is_none()
+                        {
+                            errors.push_spanned(
+                                ty,
+                                "Fallible constructor must return a `Result`, use `infallible` or `on_error` if it can't fail",
+                            );
+                        } else if on_error != ConstructorOnError::Propagate && result {
+                            errors.push_spanned(
+                                ty,
+                                "Constructor with `infallible` or `on_error` can't return a `Result`",
+                            );
+                        }
+                    }
+                }
                 if method.block.stmts.is_empty() {
                     let mut renames = HashMap::new();
                     let mut sig = method.sig.clone();
@@ -148,10 +294,10 @@ impl PublicMethod {
                         sig: Box::new(sig),
                         renames,
                         default,
-                        fallible,
+                        on_error,
                     });
                 } else {
-                    constructor = Some(ConstructorType::Custom { default, fallible });
+                    constructor = Some(ConstructorType::Custom { default, on_error });
                 }
             }
         }
@@ -171,6 +317,9 @@ impl PublicMethod {
                     name = Some(n);
                 }
             }
+            if let Some(delegate) = &attrs.delegate {
+                Self::delegate_body(method, delegate, errors);
+            }
             public = true;
         }
         if !public && constructor.is_none() {
@@ -200,6 +349,61 @@ impl PublicMethod {
             custom_body: None,
         })
     }
+    /// Fills in the empty body of a `#[public(delegate = "self.field")]` stub with a call to the
+    /// method of the same name on `self.field.borrow()`, passing the arguments through. The
+    /// stub's signature must match the target method minus its receiver. The borrow only lasts
+    /// for the call, so the delegate can't be an async method.
+    fn delegate_body(method: &mut syn::ImplItemMethod, delegate: &syn::Expr, errors: &Errors) {
+        if method.sig.receiver().is_none() {
+            errors.push_spanned(&method.sig, "`delegate` requires a `self` receiver");
+            return;
+        }
+        if let Some(asyncness) = &method.sig.asyncness {
+            errors.push_spanned(asyncness, "`delegate` not allowed on async method");
+            return;
+        }
+        if !method.block.stmts.is_empty() {
+            errors.push_spanned(
+                &method.block,
+                "Method with `delegate` must have an empty body",
+            );
+            return;
+        }
+        for arg in method.sig.inputs.iter().skip(1) {
+            if util::arg_name(arg).is_none() {
+                errors.push_spanned(arg, "Delegated method argument must be an ident");
+            }
+        }
+        let ident = &method.sig.ident;
+        let args = util::signature_args(&method.sig);
+        method.block = syn::parse_quote_spanned! { delegate.span() => {
+            #delegate.borrow().#ident(#(#args),*)
+        } };
+    }
+    /// Checks that the receiver can be forwarded from the generated method. Methods on the
+    /// wrapper of a final type aren't forwarded, so any receiver is fine there.
+    pub(crate) fn validate_receiver(&self, final_: bool, errors: &Errors) -> bool {
+        let recv = match self.sig.receiver() {
+            Some(recv) if self.constructor.is_none() => recv,
+            _ => return true,
+        };
+        if self.mode == TypeMode::Wrapper && self.target.is_none() && final_ {
+            return true;
+        }
+        let message = match ReceiverType::from_arg(recv) {
+            Some(ReceiverType::Value) if self.mode == TypeMode::Subclass => {
+                "`self` by value not supported on public method for private subclass struct, \
+                use `&self` or implement this method on the wrapper type"
+            }
+            Some(_) => return true,
+            None => {
+                "Unsupported receiver on public method, expected `&self`, `self` or \
+                `self: Pin<&Self>`"
+            }
+        };
+        errors.push_spanned(recv, message);
+        false
+    }
     #[inline]
     pub fn matches(&self, mode: TypeMode, ident: &syn::Ident) -> bool {
         self.mode == mode
@@ -241,7 +445,7 @@ impl PublicMethod {
         };
         Some(quote_spanned! { self.sig.span() =>
             fn default() -> Self {
-                #dest::#ident(#(#args),*) #unwrap
+                <#dest>::#ident(#(#args),*) #unwrap
             }
         })
     }
@@ -258,7 +462,7 @@ impl PublicMethod {
             vis,
             sig: orig_sig,
             renames,
-            fallible,
+            on_error,
             ..
         }) = self.constructor.as_ref()
         {
@@ -277,15 +481,7 @@ impl PublicMethod {
                     .unwrap_or_else(|| Cow::Owned(ident.to_string().to_kebab_case()));
                 Some(quote_spanned! { span => (#name, &#ident) })
             });
-            let expect = (!fallible).then(|| {
-                quote! { .unwrap_or_else(|e| {
-                    ::std::panic!(
-                        "Failed to construct {}: {:?}",
-                        <#wrapper_ty as #glib::StaticType>::static_type().name(),
-                        e,
-                    );
-                }) }
-            });
+            let expect = on_error.handler(wrapper_ty, glib);
             Some(quote_spanned! { orig_sig.span() =>
                 #vis #sig {
                     #![inline]
@@ -330,27 +526,31 @@ impl PublicMethod {
             TypeMode::Wrapper => wrapper_ty,
         };
         if let Some(recv) = self.sig.receiver() {
-            let has_ref = util::arg_reference(recv).is_some();
-            let this_ident = util::arg_name(recv)
-                .cloned()
-                .unwrap_or_else(|| syn::Ident::new("____this", Span::mixed_site()));
-            let cast = match has_ref {
-                true => quote! { upcast_ref },
-                false => quote! { upcast },
+            let recv_type = ReceiverType::from_arg(recv).unwrap_or(ReceiverType::Ref);
+            let this_ident = syn::Ident::new("____this", Span::mixed_site());
+            let upcast = match recv_type {
+                ReceiverType::Ref => quote! { #glib::Cast::upcast_ref::<#wrapper_ty>(self) },
+                ReceiverType::Value => quote! { #glib::Cast::upcast::<#wrapper_ty>(self) },
+                ReceiverType::PinRef => quote! {
+                    #glib::Cast::upcast_ref::<#wrapper_ty>(::std::pin::Pin::get_ref(self))
+                },
             };
             let unwrap_recv = (self.mode == TypeMode::Subclass).then(|| {
-                let ref_ = (!has_ref).then(|| quote! { & });
                 quote_spanned! { recv.span() =>
-                    let #this_ident = #glib::subclass::prelude::ObjectSubclassIsExt::imp(#ref_ #this_ident);
+                    let #this_ident = #glib::subclass::prelude::ObjectSubclassIsExt::imp(#this_ident);
                 }
             });
+            let this = match recv_type {
+                ReceiverType::PinRef => quote! { ::std::pin::Pin::new(#this_ident) },
+                _ => quote! { #this_ident },
+            };
             Some(quote_spanned! { self.sig.span() =>
                 #sig {
                     #![inline]
                     #cast_args
-                    let #this_ident = #glib::Cast::#cast::<#wrapper_ty>(self);
+                    let #this_ident = #upcast;
                     #unwrap_recv
-                    #dest::#target(#this_ident, #(#args),*) #await_
+                    <#dest>::#target(#this, #(#args),*) #await_
                 }
             })
         } else {
@@ -358,9 +558,24 @@ impl PublicMethod {
                 #sig {
                     #![inline]
                     #cast_args
-                    #dest::#target(#(#args),*) #await_
+                    <#dest>::#target(#(#args),*) #await_
                 }
             })
         }
     }
 }
+
+fn returns_result(sig: &syn::Signature) -> bool {
+    match &sig.output {
+        syn::ReturnType::Type(_, ty) => match ty.as_ref() {
+            syn::Type::Path(p) => p
+                .path
+                .segments
+                .last()
+                .map(|s| s.ident == "Result")
+                .unwrap_or(false),
+            _ => false,
+        },
+        syn::ReturnType::Default => false,
+    }
+}
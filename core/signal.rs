@@ -1,8 +1,11 @@
 use crate::{
     util::{self, Errors},
-    Concurrency, TypeBase, TypeMode,
+    Concurrency, Property, TypeBase, TypeMode,
+};
+use darling::{
+    util::{Flag, Override},
+    FromAttributes,
 };
-use darling::{util::Flag, FromAttributes};
 use heck::{ToShoutySnakeCase, ToSnakeCase};
 use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote, quote_spanned};
@@ -54,11 +57,18 @@ struct SignalAttrs {
     action: Flag,
     no_hooks: Flag,
     must_collect: Flag,
-    deprecated: Flag,
+    /// Either a bare flag or a deprecation notice, also used for the `#[deprecated]` attribute
+    /// on the generated methods.
+    deprecated: Option<Override<syn::LitStr>>,
     #[darling(rename = "override")]
     override_: Flag,
     connect: Option<bool>,
+    dispatch_main: Flag,
     name: Option<syn::LitStr>,
+    default_return: Option<syn::Expr>,
+    /// A function `fn(&SignalInvocationHint, &mut Value, &Value) -> bool` passed directly to
+    /// the signal builder, as an alternative to an `#[accumulator]` method.
+    accumulator: Option<syn::Path>,
 }
 
 impl SignalAttrs {
@@ -90,8 +100,15 @@ pub struct Signal {
     pub connect: bool,
     pub override_: bool,
     pub sig: Option<syn::Signature>,
+    /// Names of the signal parameters, excluding the receiver. Arguments bound with a pattern
+    /// other than an identifier are named `argN` after their position.
+    pub param_names: Vec<syn::Ident>,
     pub handler: bool,
     pub accumulator: Option<syn::Signature>,
+    pub raw_accumulator: Option<syn::Path>,
+    pub deprecated: Option<syn::LitStr>,
+    pub default_return: Option<syn::Expr>,
+    pub dispatch_main: bool,
     pub mode: TypeMode,
 }
 
@@ -113,6 +130,9 @@ impl Signal {
                         method
                             .attrs
                             .push(syn::parse_quote! { #[allow(unused_variables)] });
+                        if !matches!(method.sig.output, syn::ReturnType::Default) {
+                            method.block = syn::parse_quote! { { ::std::unreachable!() } };
+                        }
                     }
                     Self::from_handler(m, attr, base, mode, signals, errors);
                 } else if let Some(attrs) = util::extract_attrs(&mut method.attrs, "accumulator") {
@@ -122,16 +142,46 @@ impl Signal {
             }
         }
     }
+    pub(crate) fn validate_concurrency(
+        signals: &[Self],
+        concurrency: Concurrency,
+        errors: &Errors,
+    ) {
+        if concurrency != Concurrency::None {
+            return;
+        }
+        for signal in signals.iter().filter(|s| s.dispatch_main) {
+            if let Some(sig) = &signal.sig {
+                errors.push_spanned(&sig.ident, "`dispatch_main` requires a `sync` type");
+            }
+        }
+    }
     pub(crate) fn validate_many(signals: &[Self], errors: &Errors) {
         for signal in signals {
             if let Some(sig) = &signal.sig {
-                if signal.accumulator.is_some() && matches!(sig.output, syn::ReturnType::Default) {
+                let has_accumulator =
+                    signal.accumulator.is_some() || signal.raw_accumulator.is_some();
+                if has_accumulator && matches!(sig.output, syn::ReturnType::Default) {
                     errors.push_spanned(sig, "Signal with accumulator must have return type");
                 }
             } else {
                 let acc = signal.accumulator.as_ref().expect("no accumulator");
                 errors.push_spanned(acc, format!("No definition for signal `{}`", signal.name));
             }
+            if let Some(acc) = &signal.raw_accumulator {
+                if signal.override_ {
+                    errors.push_spanned(acc, "Accumulator not allowed on overriden signal");
+                }
+                if let Some(method) = &signal.accumulator {
+                    errors.push_spanned(
+                        &method.ident,
+                        format!(
+                            "Signal `{}` already has an accumulator from `accumulator = \"...\"`",
+                            signal.name
+                        ),
+                    );
+                }
+            }
             if let Some(acc) = &signal.accumulator {
                 if signal.override_ {
                     errors.push_spanned(acc, "Accumulator not allowed on overriden signal");
@@ -139,6 +189,65 @@ impl Signal {
             }
         }
     }
+    /// Signals and properties share the method namespace of the wrapper type. A signal `foo`
+    /// generates `emit_foo`, `connect_foo` and `connect_foo_local`; a property `foo` generates
+    /// `foo`, `set_foo`, `foo_value`, `bind_foo_full`, `borrow_foo`, `notify_foo`,
+    /// `connect_foo_notify` and `connect_foo_notify_local`. A signal clashes with a property when
+    /// they have the same name or when any of those method names overlap, e.g. signal
+    /// `foo-notify` and property `foo`.
+    pub(crate) fn validate_property_names(
+        signals: &[Self],
+        properties: &[Property],
+        errors: &Errors,
+    ) {
+        let properties = properties
+            .iter()
+            .filter(|p| p.override_.is_none())
+            .map(|p| {
+                let field = p.name.field_name();
+                let methods = [
+                    field.to_string(),
+                    format!("set_{}", field),
+                    format!("{}_value", field),
+                    format!("bind_{}_full", field),
+                    format!("borrow_{}", field),
+                    format!("notify_{}", field),
+                    format!("connect_{}_notify", field),
+                    format!("connect_{}_notify_local", field),
+                ];
+                (p.name.to_string(), methods)
+            })
+            .collect::<Vec<_>>();
+        for signal in signals.iter().filter(|s| !s.override_) {
+            let span = signal
+                .sig
+                .as_ref()
+                .map(|s| s.ident.span())
+                .unwrap_or_else(|| signal.ident.span());
+            let field = signal.name.to_snake_case();
+            let methods = [
+                format!("emit_{}", field),
+                format!("connect_{}", field),
+                format!("connect_{}_local", field),
+            ];
+            for (prop_name, prop_methods) in &properties {
+                if *prop_name == signal.name {
+                    errors.push(
+                        span,
+                        format!("Signal `{}` has the same name as a property", signal.name),
+                    );
+                } else if let Some(method) = methods.iter().find(|m| prop_methods.contains(m)) {
+                    errors.push(
+                        span,
+                        format!(
+                            "Signal `{}` and property `{}` both generate method `{}`",
+                            signal.name, prop_name, method
+                        ),
+                    );
+                }
+            }
+        }
+    }
     #[inline]
     #[allow(clippy::ptr_arg)]
     fn from_handler(
@@ -184,8 +293,61 @@ impl Signal {
         signal.flags = attr.flags();
         signal.connect = attr.connect.unwrap_or(true);
         signal.override_ = attr.override_.is_some();
+        signal.deprecated = attr.deprecated.map(|d| match d {
+            Override::Explicit(note) => note,
+            Override::Inherit => syn::LitStr::new(
+                &format!("signal `{}` is deprecated", name),
+                signal.ident.span(),
+            ),
+        });
+        if let Some(deprecated) = signal.deprecated.as_ref().filter(|_| signal.override_) {
+            errors.push_spanned(deprecated, "`deprecated` not allowed on override");
+        }
+        signal.param_names = method
+            .sig
+            .inputs
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::FnArg::Typed(t) => Some(t),
+                _ => None,
+            })
+            .enumerate()
+            .map(|(index, arg)| match &*arg.pat {
+                syn::Pat::Ident(syn::PatIdent { ident, .. }) => ident.clone(),
+                _ => format_ident!("arg{}", index, span = arg.pat.span()),
+            })
+            .collect();
         signal.sig = Some(method.sig);
         signal.handler = !method.block.stmts.is_empty();
+        signal.default_return = attr.default_return;
+        signal.raw_accumulator = attr.accumulator;
+        signal.dispatch_main = attr.dispatch_main.is_some();
+        if signal.dispatch_main {
+            let sig = signal.sig.as_ref().unwrap();
+            if signal.override_ {
+                errors.push_spanned(&sig.ident, "`dispatch_main` not allowed on override");
+            } else if !matches!(sig.output, syn::ReturnType::Default) {
+                errors.push_spanned(
+                    &sig.output,
+                    "`dispatch_main` signal can't return a value, handlers run after emission returns",
+                );
+            }
+        }
+        if let Some(default_return) = &signal.default_return {
+            if signal.handler {
+                errors.push_spanned(
+                    default_return,
+                    "`default_return` not allowed on signal with a class handler",
+                );
+            } else if matches!(
+                signal.sig.as_ref().unwrap().output,
+                syn::ReturnType::Default
+            ) {
+                errors.push_spanned(default_return, "`default_return` requires a return type");
+            } else if signal.override_ {
+                errors.push_spanned(default_return, "`default_return` not allowed on override");
+            }
+        }
         if base == TypeBase::Interface && signal.override_ {
             errors.push_spanned(&signal.ident, "`override` not allowed on interface signal");
             signal.override_ = false;
@@ -240,8 +402,13 @@ impl Signal {
             connect: false,
             override_: false,
             sig: None,
+            param_names: Vec::new(),
             handler: false,
             accumulator: None,
+            raw_accumulator: None,
+            deprecated: None,
+            default_return: None,
+            dispatch_main: false,
             mode,
         }
     }
@@ -413,7 +580,7 @@ impl Signal {
                     #args_ident: &[#glib::Value]
                 ) -> ::std::option::Option<#glib::Value> {
                     #(#args_unwrap)*
-                    let #ret_ident = #dest::#method_name(#(#arg_names),*);
+                    let #ret_ident = <#dest>::#method_name(#(#arg_names),*);
                     #glib::closure::ToClosureReturnValue::to_closure_return_value(&#ret_ident)
                 }
                 let #builder = #builder.class_handler(#handler_name);
@@ -446,7 +613,7 @@ impl Signal {
                 ) -> ::std::primitive::bool {
                     let #cur_accu = #accu.get().unwrap();
                     let #value = #value.get().unwrap();
-                    let (#next, #ret) = match #dest::#method_name(#call_args) {
+                    let (#next, #ret) = match <#dest>::#method_name(#call_args) {
                         ::std::ops::ControlFlow::Continue(#next) => (#next, true),
                         ::std::ops::ControlFlow::Break(#next) => (#next, false),
                     };
@@ -458,6 +625,13 @@ impl Signal {
                 let #builder = #builder.accumulator(#acc_name);
             }
         });
+        let accumulator = accumulator.or_else(|| {
+            self.raw_accumulator.as_ref().map(|path| {
+                quote_spanned! { path.span() =>
+                    let #builder = #builder.accumulator(#path);
+                }
+            })
+        });
         let flags = (!flags.is_empty()).then(|| {
             let flags = flags.tokens(glib);
             quote! { let #builder = #builder.flags(#flags); }
@@ -514,7 +688,7 @@ impl Signal {
                 #args_ident: &[#glib::Value]
             ) -> ::std::option::Option<#glib::Value> {
                 #(#args_unwrap)*
-                let #ret_ident = #dest::#method_name(#(#arg_names),*);
+                let #ret_ident = <#dest>::#method_name(#(#arg_names),*);
                 #glib::closure::ToClosureReturnValue::to_closure_return_value(&#ret_ident)
             }
             #glib::subclass::object::ObjectClassSubclassExt::override_signal_class_handler(
@@ -524,7 +698,12 @@ impl Signal {
             );
         }})
     }
-    pub(crate) fn chain_definition(&self, mode: TypeMode, glib: &syn::Path) -> Option<TokenStream> {
+    pub(crate) fn chain_definition(
+        &self,
+        mode: TypeMode,
+        vis: &syn::Visibility,
+        glib: &syn::Path,
+    ) -> Option<TokenStream> {
         if !self.override_ {
             return None;
         }
@@ -589,8 +768,18 @@ impl Signal {
             }
             syn::ReturnType::Default => None,
         };
+        let doc = format!(
+            "Chains up to the class handler of `{}` overridden by this type. Only valid while \
+             called from the overriding class handler during an emission. The parent handler \
+             runs in the same stage as the override: before handlers connected with `connect` for \
+             `run_first` signals, after them but before `connect_after` handlers for `run_last` \
+             signals. Does nothing if no parent class has a handler for the signal, and returns \
+             the default value of the return type.",
+            name
+        );
         Some(quote_spanned! { sig.span() =>
-            fn #method_name(&#self_ident, #(#arg_types),*) #output {
+            #[doc = #doc]
+            #vis fn #method_name(&#self_ident, #(#arg_types),*) #output {
                 #declare_result
                 let #values_ident = [
                     #glib::ToValue::to_value(&#unwrap_recv),
@@ -628,7 +817,16 @@ impl Signal {
             fn #method_name(&#self_ident, #details_arg #(#arg_types),*) #output
         })
     }
-    fn emit_definition(&self, glib: &syn::Path) -> Option<TokenStream> {
+    /// rustc rejects `#[deprecated]` on items of a trait impl, so it goes on the ext trait
+    /// prototypes, or inside the method bodies of final types.
+    fn deprecated_attr(&self, style: syn::AttrStyle) -> Option<TokenStream> {
+        let note = self.deprecated.as_ref()?;
+        Some(match style {
+            syn::AttrStyle::Outer => quote! { #[deprecated = #note] },
+            syn::AttrStyle::Inner(_) => quote! { #![deprecated = #note] },
+        })
+    }
+    fn emit_definition(&self, final_: bool, glib: &syn::Path) -> Option<TokenStream> {
         let proto = self.emit_prototype(glib)?;
         let sig = self.sig.as_ref()?;
         let arg_types = self.arg_types();
@@ -665,9 +863,34 @@ impl Signal {
         } else {
             emit
         };
+        // Without a class handler GLib would hand back the zero value of the return type (which
+        // fails to convert for e.g. `String`), so return the declared default when nothing is
+        // connected.
+        let default_return = self.default_return.as_ref().map(|default_return| {
+            let details = if self.flags.contains(SignalFlags::DETAILED) {
+                quote! { #details_ident }
+            } else {
+                quote! { ::std::option::Option::None }
+            };
+            quote! {
+                if !#glib::signal::signal_has_handler_pending(
+                    #self_ident,
+                    *#signal_id_cell,
+                    #details,
+                    false,
+                ) {
+                    return #default_return;
+                }
+            }
+        });
+        let deprecated = final_
+            .then(|| self.deprecated_attr(syn::AttrStyle::Inner(Default::default())))
+            .flatten();
         Some(quote_spanned! { sig.span() =>
             #proto {
                 #![inline]
+                #deprecated
+                #default_return
                 #body
             }
         })
@@ -718,10 +941,17 @@ impl Signal {
         &self,
         concurrency: Concurrency,
         local: bool,
+        final_: bool,
         glib: &syn::Path,
     ) -> Option<TokenStream> {
         let proto = self.connect_prototype(concurrency, local, glib)?;
         let sig = self.sig.as_ref()?;
+        let deprecated = final_
+            .then(|| self.deprecated_attr(syn::AttrStyle::Inner(Default::default())))
+            .flatten();
+        if self.dispatch_main {
+            return Some(self.dispatch_main_connect_definition(proto, deprecated, glib));
+        }
         let arg_names = self.arg_names().skip(1);
         let self_ty = parse_quote! { Self };
 
@@ -750,14 +980,25 @@ impl Signal {
             },
             _ => quote! { ::core::option::Option::None },
         };
+        // `connect_local_id` wraps the handler in a `ThreadGuard`, which panics if the handler is
+        // invoked or dropped on any thread other than the one that connected it.
+        let local_doc = local.then(|| {
+            quote! {
+                #![doc = "Connects a handler that is not required to be `Send` or `Sync`. The handler is bound to the calling thread: emitting the signal from another thread, or dropping the last reference to the object there while the handler is still connected, panics."]
+            }
+        });
         Some(quote_spanned! { sig.span() =>
             #proto {
                 #![inline]
+                #local_doc
+                #deprecated
                 <Self as #glib::object::ObjectExt>::#call(
                     #self_ident,
                     *#signal_id_cell,
                     #details,
                     false,
+                    // Borrowed arguments like `&str` point into `args`, so they stay valid
+                    // for as long as the handler runs.
                     move |#args_ident| {
                         let #recv_ident = #args_ident[0].get::<Self>().unwrap();
                         #(#args_unwrap)*
@@ -768,32 +1009,90 @@ impl Signal {
             }
         })
     }
+    /// Handlers of `dispatch_main` signals are re-invoked on the default main context. The
+    /// arguments are converted to `SendValue`s on the emitting thread and unpacked again inside
+    /// the main context, so every argument type must be `Send`.
+    fn dispatch_main_connect_definition(
+        &self,
+        proto: TokenStream,
+        deprecated: Option<TokenStream>,
+        glib: &syn::Path,
+    ) -> TokenStream {
+        let sig = self.sig.as_ref().unwrap();
+        let arg_names = self.arg_names().skip(1).collect::<Vec<_>>();
+        let self_ty = parse_quote! { Self };
+        let self_ident = syn::Ident::new("self", Span::mixed_site());
+        let func_ident = syn::Ident::new("func", Span::mixed_site());
+        let args_ident = syn::Ident::new("args", Span::mixed_site());
+        let send_args_ident = syn::Ident::new("send_args", Span::mixed_site());
+        let recv_ident = syn::Ident::new("recv", Span::mixed_site());
+        let args_unwrap = self.args_unwrap(&args_ident, &self_ty, glib).skip(1);
+        let send_args_unwrap = self.args_unwrap(&send_args_ident, &self_ty, glib).skip(1);
+        let signal_id_cell = self.signal_id_cell_ident();
+        let details = if self.flags.contains(SignalFlags::DETAILED) {
+            quote_spanned! { Span::mixed_site() => details }
+        } else {
+            quote! { ::std::option::Option::None }
+        };
+        quote_spanned! { sig.span() =>
+            #proto {
+                #![inline]
+                #![doc = "The handler is invoked on the default `glib::MainContext` rather than on the emitting thread. It runs after the emission has returned when emitted from another thread, so stopping the emission from the handler has no effect."]
+                #deprecated
+                let #func_ident = ::std::sync::Arc::new(#func_ident);
+                <Self as #glib::object::ObjectExt>::connect_id(
+                    #self_ident,
+                    *#signal_id_cell,
+                    #details,
+                    false,
+                    move |#args_ident| {
+                        let #recv_ident = #args_ident[0].get::<Self>().unwrap();
+                        #(#args_unwrap)*
+                        let #send_args_ident = [
+                            #glib::value::ToSendValue::to_send_value(&#recv_ident),
+                            #(#glib::value::ToSendValue::to_send_value(&#arg_names)),*
+                        ];
+                        let #func_ident = ::std::sync::Arc::clone(&#func_ident);
+                        #glib::MainContext::default().invoke(move || {
+                            let #recv_ident = #send_args_ident[0].get::<Self>().unwrap();
+                            #(#send_args_unwrap)*
+                            #func_ident(&#recv_ident, #(#arg_names),*);
+                        });
+                        ::core::option::Option::None
+                    },
+                )
+            }
+        }
+    }
     pub(crate) fn method_prototypes(
         &self,
         concurrency: Concurrency,
         glib: &syn::Path,
     ) -> Vec<TokenStream> {
+        let deprecated = self.deprecated_attr(syn::AttrStyle::Outer);
         [
             self.emit_prototype(glib),
             self.connect_prototype(concurrency, false, glib),
-            (concurrency != Concurrency::None)
+            (concurrency != Concurrency::None && !self.dispatch_main)
                 .then(|| self.connect_prototype(Concurrency::None, true, glib))
                 .flatten(),
         ]
         .into_iter()
         .flatten()
+        .map(|proto| quote! { #deprecated #proto })
         .collect()
     }
     pub(crate) fn method_definitions(
         &self,
         concurrency: Concurrency,
+        final_: bool,
         glib: &syn::Path,
     ) -> Vec<TokenStream> {
         [
-            self.emit_definition(glib),
-            self.connect_definition(concurrency, false, glib),
-            (concurrency != Concurrency::None)
-                .then(|| self.connect_definition(Concurrency::None, true, glib))
+            self.emit_definition(final_, glib),
+            self.connect_definition(concurrency, false, final_, glib),
+            (concurrency != Concurrency::None && !self.dispatch_main)
+                .then(|| self.connect_definition(Concurrency::None, true, final_, glib))
                 .flatten(),
         ]
         .into_iter()
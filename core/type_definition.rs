@@ -1,5 +1,5 @@
 use crate::{
-    property::{Properties, Property},
+    property::{Properties, Property, PropertyTypeMapping},
     public_method::PublicMethod,
     signal::Signal,
     util::{self, Errors},
@@ -137,6 +137,7 @@ impl TypeDefinition {
             );
             return def;
         }
+        let type_mappings = PropertyTypeMapping::many_from_attrs(&mut def.module.attrs, errors);
         let glib = def.glib();
         let (_, items) = def.module.content.as_mut().unwrap();
         let mut struct_ = None;
@@ -320,6 +321,15 @@ impl TypeDefinition {
             if generics.is_none() {
                 generics = Some(impl_.generics.clone());
             }
+            for prop in Property::many_from_items(&mut impl_.items, base, mode, errors) {
+                if def.properties.iter().any(|p| p.name == prop.name) {
+                    errors.push(
+                        prop.name.span(),
+                        format!("Duplicate definition for property `{}`", prop.name),
+                    );
+                }
+                def.properties.push(prop);
+            }
             Signal::many_from_items(&mut impl_.items, base, mode, &mut def.signals, errors);
             def.public_methods.extend(PublicMethod::many_from_items(
                 &mut impl_.items,
@@ -334,11 +344,56 @@ impl TypeDefinition {
                 errors,
             ));
         }
+        for prop in &mut def.properties {
+            prop.apply_type_mappings(&type_mappings);
+        }
         Signal::validate_many(&def.signals, errors);
+        Signal::validate_property_names(&def.signals, &def.properties, errors);
+        for prop in &def.properties {
+            if let Some(signal_name) = &prop.changed_signal {
+                match def.signals.iter().find(|s| s.name == signal_name.value()) {
+                    Some(signal) => {
+                        let valid = match &signal.sig {
+                            Some(sig) => {
+                                sig.inputs.len() == 1
+                                    && matches!(sig.output, syn::ReturnType::Default)
+                            }
+                            None => true,
+                        };
+                        if !valid {
+                            errors.push_spanned(
+                                signal_name,
+                                format!(
+                                    "Signal `{}` used as `changed_signal` must take no arguments and return nothing",
+                                    signal.name
+                                ),
+                            );
+                        }
+                    }
+                    None => errors.push_spanned(
+                        signal_name,
+                        format!(
+                            "No signal named `{}` for `changed_signal`",
+                            signal_name.value()
+                        ),
+                    ),
+                }
+            }
+        }
         if let Some(name) = name {
             def.name = name;
         }
-        if let Some(generics) = generics {
+        if let Some(mut generics) = generics {
+            for param in generics.const_params() {
+                errors.push_spanned(
+                    param,
+                    "Const generics not supported, all instantiations would share one GType",
+                );
+            }
+            generics.params = std::mem::take(&mut generics.params)
+                .into_iter()
+                .filter(|p| !matches!(p, syn::GenericParam::Const(_)))
+                .collect();
             def.generics = generics;
         }
         def.methods_item_indices = impls.into_iter().collect();
@@ -355,7 +410,7 @@ impl TypeDefinition {
 
         let name = &self.name;
         let glib = self.glib();
-        let generics = &self.generics;
+        let (_, generics, _) = self.generics.split_for_impl();
 
         let recv = match ctx {
             Internal => parse_quote! { Self },
@@ -461,6 +516,60 @@ impl TypeDefinition {
             .iter_mut()
             .find(|pm| pm.matches(mode, ident))
     }
+    /// Public methods share the wrapper's method namespace with the accessors generated for
+    /// properties and signals. A duplicate name either fails to compile with an error inside
+    /// generated code or, through the ext trait, makes calls ambiguous, so report it on the
+    /// public method instead. Clashes between properties and signals are checked in
+    /// `Signal::validate_property_names`.
+    pub(crate) fn validate_public_method_names(&self, errors: &Errors) {
+        let go = &self.crate_path;
+        let glib = self.glib();
+        let method_ident = |proto: TokenStream| {
+            syn::parse2::<syn::TraitItemMethod>(quote! { #proto; })
+                .ok()
+                .map(|m| m.sig.ident.to_string())
+        };
+        let mut generated = HashMap::new();
+        for prop in &self.properties {
+            for proto in prop.method_prototypes(self.concurrency, go) {
+                if let Some(ident) = method_ident(proto) {
+                    generated.insert(ident, format!("property `{}`", prop.name));
+                }
+            }
+        }
+        for signal in &self.signals {
+            for proto in signal.method_prototypes(self.concurrency, &glib) {
+                if let Some(ident) = method_ident(proto) {
+                    generated.insert(ident, format!("signal `{}`", signal.name));
+                }
+            }
+        }
+        for method in &self.public_methods {
+            let ident = match method.prototype(&glib).and_then(method_ident) {
+                Some(ident) => ident,
+                None => continue,
+            };
+            if let Some(origin) = generated.get(&ident) {
+                errors.push_spanned(
+                    &method.sig.ident,
+                    format!(
+                        "Public method `{}` has the same name as a method generated for {}",
+                        ident, origin
+                    ),
+                );
+            }
+        }
+    }
+    /// Drops public methods with receivers that can't be forwarded, so the error isn't followed
+    /// by type mismatches in the generated code. Receivers on private interface structs are
+    /// already rejected while parsing.
+    pub(crate) fn validate_public_method_receivers(&mut self, final_: bool, errors: &Errors) {
+        let base = self.base;
+        self.public_methods.retain(|method| {
+            (base == TypeBase::Interface && method.mode == TypeMode::Subclass)
+                || method.validate_receiver(final_, errors)
+        });
+    }
     pub fn add_custom_stmt(&self, name: &str, stmt: syn::Stmt) {
         let mut stmts = self.custom_stmts.borrow_mut();
         if let Some(stmts) = stmts.get_mut(name) {
@@ -530,10 +639,34 @@ impl TypeDefinition {
             }
         }
     }
+    /// Generic arguments for the parent, interfaces and prerequisites aliases. Only the type
+    /// parameters used in the aliased type are included, since a type alias can't have unused
+    /// parameters.
+    pub(crate) fn alias_args(&self, ty: &TokenStream) -> Option<TokenStream> {
+        fn uses(tokens: TokenStream, ident: &syn::Ident) -> bool {
+            tokens.into_iter().any(|tt| match tt {
+                proc_macro2::TokenTree::Ident(i) => &i == ident,
+                proc_macro2::TokenTree::Group(g) => uses(g.stream(), ident),
+                _ => false,
+            })
+        }
+        let params = self
+            .generics
+            .type_params()
+            .map(|p| &p.ident)
+            .filter(|ident| uses(ty.clone(), ident))
+            .collect::<Vec<_>>();
+        (!params.is_empty()).then(|| quote! { <#(#params),*> })
+    }
     #[inline]
     pub fn trait_head(&self, ty: &syn::Path, trait_: TokenStream) -> TokenStream {
         self.trait_head_with_params(ty, trait_, None::<[syn::GenericParam; 0]>)
     }
+    /// Overridden properties (`override_class`/`override_iface`) are in the same list as a
+    /// `ParamSpecOverride`, so they take a property id and go through the generated
+    /// `set_property`/`property` like any other property. If the subclass also defines
+    /// `properties`, its pspecs come first and `_GENERATED_PROPERTIES_BASE_INDEX` records where the
+    /// generated ones start, which is subtracted from the id before matching on the index.
     pub(crate) fn properties_method(&self) -> Option<TokenStream> {
         let has_method = self.has_method(TypeMode::Subclass, "properties");
         let custom = self.custom_stmts_for("properties");
@@ -547,7 +680,8 @@ impl TypeDefinition {
             TypeMode::Subclass,
             TypeContext::External,
         );
-        let defs = self.properties.iter().map(|p| p.definition(go));
+        let ty = self.type_(TypeMode::Subclass, TypeMode::Wrapper, TypeContext::External);
+        let defs = self.properties.iter().map(|p| p.definition(&ty, go));
         let extra = has_method.then(|| {
             quote_spanned! { Span::mixed_site() =>
                 properties.extend(#sub_ty::properties());
@@ -592,7 +726,7 @@ impl TypeDefinition {
         let defs = self
             .signals
             .iter()
-            .map(|s| s.definition(&ty, &sub_ty, &glib));
+            .filter_map(|s| s.definition(&ty, &sub_ty, &glib));
         let extra = has_method.then(|| {
             quote_spanned! { Span::mixed_site() =>
                 signals.extend(#sub_ty::signals());
@@ -617,6 +751,7 @@ impl TypeDefinition {
         let glib = self.glib();
         self.properties
             .iter()
+            .filter(|p| p.vis.is_none())
             .flat_map(|p| p.method_prototypes(self.concurrency, go))
             .chain(
                 self.signals
@@ -654,15 +789,19 @@ impl TypeDefinition {
             let go = self.crate_path.clone();
             let ty = ty.clone();
             let properties_path = self.method_path("properties", TypeMode::Subclass);
-            self.properties.iter().enumerate().flat_map(move |(i, p)| {
-                p.method_definitions(i, &ty, self.concurrency, &properties_path, &go)
-            })
+            self.properties
+                .iter()
+                .enumerate()
+                .filter(|(_, p)| p.vis.is_none())
+                .flat_map(move |(i, p)| {
+                    p.method_definitions(i, &ty, self.concurrency, &properties_path, &go)
+                })
         };
         let signals = {
             let glib = self.glib();
             self.signals
                 .iter()
-                .flat_map(move |s| s.method_definitions(self.concurrency, &glib))
+                .flat_map(move |s| s.method_definitions(self.concurrency, final_, &glib))
         };
         let public_methods = {
             let glib = self.glib();
@@ -687,8 +826,22 @@ impl TypeDefinition {
             .chain(public_methods)
             .chain(virtual_methods)
     }
+    /// Accessors for properties with their own `vis`. These always go in an inherent impl on the
+    /// wrapper, since methods of the ext trait can't have per-method visibility.
+    fn scoped_property_definitions(&self) -> impl Iterator<Item = TokenStream> + '_ {
+        let go = self.crate_path.clone();
+        let ty = self.type_(TypeMode::Subclass, TypeMode::Wrapper, TypeContext::External);
+        let properties_path = self.method_path("properties", TypeMode::Subclass);
+        self.properties
+            .iter()
+            .enumerate()
+            .filter_map(move |(i, p)| {
+                let vis = p.vis.as_ref()?;
+                let defs = p.method_definitions(i, &ty, self.concurrency, &properties_path, &go);
+                Some(quote! { #(#vis #defs)* })
+            })
+    }
     pub(crate) fn public_methods(&self, trait_name: Option<&syn::Ident>) -> Option<TokenStream> {
-        let go = &self.crate_path;
         let glib = self.glib();
         let final_ = trait_name.is_none();
         let mut items = self.public_method_definitions(final_).peekable();
@@ -728,12 +881,12 @@ impl TypeDefinition {
                 }
             })
         });
-        let has_wrapper_statics = statics.peek().is_some() || wrapper_statics.peek().is_some();
+        let mut scoped_properties = self.scoped_property_definitions().peekable();
+        let has_wrapper_statics = statics.peek().is_some()
+            || wrapper_statics.peek().is_some()
+            || scoped_properties.peek().is_some();
         let has_subclass_statics = subclass_statics.peek().is_some();
-        let async_trait = match self.concurrency {
-            Concurrency::None => quote! { #[#go::async_trait::async_trait(?Send)] },
-            Concurrency::SendSync => quote! { #[#go::async_trait::async_trait] },
-        };
+        let async_trait = self.async_trait_attr();
         let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
         if let Some(trait_name) = trait_name {
             let items = items.peek().is_some().then(|| {
@@ -742,13 +895,14 @@ impl TypeDefinition {
                 generics.params.push(param);
                 let (impl_generics, _, _) = generics.split_for_impl();
                 let protos = self.public_method_prototypes();
+                let trait_generics = &self.generics;
                 quote! {
                     #async_trait
-                    #vis trait #trait_name: 'static {
+                    #vis trait #trait_name #trait_generics: 'static #where_clause {
                         #(#protos;)*
                     }
                     #async_trait
-                    impl #impl_generics #trait_name for #type_ident #where_clause {
+                    impl #impl_generics #trait_name #type_generics for #type_ident #where_clause {
                         #(#items)*
                     }
                 }
@@ -758,6 +912,7 @@ impl TypeDefinition {
                     impl #impl_generics super::#name #type_generics #where_clause {
                         #(pub #statics)*
                         #(#wrapper_statics)*
+                        #(#scoped_properties)*
                     }
                 }
             });
@@ -780,6 +935,7 @@ impl TypeDefinition {
                     #(pub #items)*
                     #(pub #statics)*
                     #(#wrapper_statics)*
+                    #(#scoped_properties)*
                 }
                 impl #impl_generics #name #type_generics #where_clause {
                     #(#subclass_statics)*
@@ -882,7 +1038,11 @@ impl TypeDefinition {
             .virtual_methods
             .iter()
             .map(|m| m.default_definition(ext_trait_name, &glib));
+        let async_trait = self
+            .has_async_virtual_methods()
+            .then(|| self.async_trait_attr());
         Some(quote! {
+            #async_trait
             #vis trait #trait_name: #parent_trait + 'static {
                 #(#virtual_methods_default)*
             }
@@ -909,15 +1069,34 @@ impl TypeDefinition {
             .virtual_methods
             .iter()
             .map(|m| m.parent_definition(&ty, &glib));
+        let async_trait = self
+            .has_async_virtual_methods()
+            .then(|| self.async_trait_attr());
         Some(quote! {
+            #async_trait
             #vis trait #ext_trait_name: #glib::subclass::types::ObjectSubclass {
                 #(#parent_method_protos;)*
             }
+            #async_trait
             impl<#type_ident: #trait_name> #ext_trait_name for #type_ident {
                 #(#parent_method_definitions)*
             }
         })
     }
+    fn async_trait_attr(&self) -> TokenStream {
+        let go = &self.crate_path;
+        match self.concurrency {
+            Concurrency::None => quote! { #[#go::async_trait::async_trait(?Send)] },
+            Concurrency::SendSync => quote! { #[#go::async_trait::async_trait] },
+        }
+    }
+    /// Async virtual methods are lowered with `async_trait`, so overriding them requires
+    /// annotating the `impl` of the class trait with the same attribute.
+    fn has_async_virtual_methods(&self) -> bool {
+        self.virtual_methods
+            .iter()
+            .any(|m| m.sig.asyncness.is_some())
+    }
     pub(crate) fn virtual_traits(
         &self,
         trait_name: Option<&syn::Ident>,
@@ -938,7 +1117,7 @@ impl TypeDefinition {
         let glib = self.glib();
 
         for signal in &self.signals {
-            if let Some(chain) = signal.chain_definition(mode, &glib) {
+            if let Some(chain) = signal.chain_definition(mode, &self.inner_vis, &glib) {
                 methods.push(chain);
             }
         }
@@ -980,8 +1159,42 @@ impl TypeDefinition {
             });
         }
 
+        items.extend(self.property_filters());
+
         items
     }
+    fn property_filters(&self) -> Option<TokenStream> {
+        if self.properties.is_empty()
+            && !self.has_method(TypeMode::Subclass, "properties")
+            && !self.has_custom_stmts("properties")
+        {
+            return None;
+        }
+        let name = &self.name;
+        let glib = self.glib();
+        let vis = &self.inner_vis;
+        let properties_path = self.method_path("properties", TypeMode::Subclass);
+        let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
+        Some(quote_spanned! { Span::mixed_site() =>
+            impl #impl_generics super::#name #type_generics #where_clause {
+                #vis fn readable_properties() -> ::std::vec::Vec<&'static #glib::ParamSpec> {
+                    #properties_path()
+                        .iter()
+                        .filter(|p| p.flags().contains(#glib::ParamFlags::READABLE))
+                        .collect()
+                }
+                #vis fn writable_properties() -> ::std::vec::Vec<&'static #glib::ParamSpec> {
+                    #properties_path()
+                        .iter()
+                        .filter(|p| {
+                            p.flags().contains(#glib::ParamFlags::WRITABLE)
+                                && !p.flags().contains(#glib::ParamFlags::CONSTRUCT_ONLY)
+                        })
+                        .collect()
+                }
+            }
+        })
+    }
 }
 
 impl Spanned for TypeDefinition {
@@ -127,6 +127,34 @@ where
     T::from_list(&args).map_err(|e| errors.push_darling(e)).ok()
 }
 
+/// Like [`darling::util::PathList`], but also accepts string literals so that paths can carry
+/// generic arguments, e.g. `extends("Parent<u32>")`.
+#[derive(Debug, Default, Clone)]
+pub struct GenericPathList(Vec<syn::Path>);
+
+impl std::ops::Deref for GenericPathList {
+    type Target = Vec<syn::Path>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl darling::FromMeta for GenericPathList {
+    fn from_list(items: &[syn::NestedMeta]) -> darling::Result<Self> {
+        let mut paths = Vec::with_capacity(items.len());
+        for item in items {
+            match item {
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) => paths.push(path.clone()),
+                syn::NestedMeta::Lit(syn::Lit::Str(s)) => {
+                    paths.push(s.parse().map_err(darling::Error::from)?)
+                }
+                item => return Err(darling::Error::unexpected_type("non-path").with_span(item)),
+            }
+        }
+        Ok(Self(paths))
+    }
+}
+
 #[inline]
 pub fn parse_attributes<T>(attrs: &[syn::Attribute], errors: &Errors) -> T
 where
@@ -163,6 +191,17 @@ pub(crate) fn is_valid_name(name: &str) -> bool {
     }
 }
 
+/// Mirrors the check done by `g_type_register_static`: at least three characters, starting with
+/// a letter or `_`, followed by letters, digits, `-`, `_` or `+`.
+pub(crate) fn is_valid_type_name(name: &str) -> bool {
+    let mut iter = name.chars();
+    match iter.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    name.len() >= 3 && iter.all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '+'))
+}
+
 pub fn arg_reference(arg: &syn::FnArg) -> Option<TokenStream> {
     match arg {
         syn::FnArg::Receiver(syn::Receiver {
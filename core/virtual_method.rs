@@ -1,10 +1,10 @@
 use crate::{
     util::{self, Errors},
-    TypeBase, TypeMode,
+    Concurrency, TypeBase, TypeMode,
 };
 use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote, quote_spanned};
-use syn::{parse_quote, parse_quote_spanned, spanned::Spanned};
+use syn::{parse_quote, parse_quote_spanned, spanned::Spanned, visit_mut::VisitMut};
 
 #[derive(Debug)]
 pub struct VirtualMethod {
@@ -43,11 +43,17 @@ impl VirtualMethod {
         mode: TypeMode,
         errors: &Errors,
     ) -> Option<Self> {
-        if let Some(async_) = &method.sig.asyncness {
-            errors.push_spanned(
-                async_,
-                "Virtual method cannot be async, return a Future instead",
-            );
+        // An empty body on a method with a return type would not compile, so it defaults to
+        // `Default::default()`. Types without a `Default` impl fail on the return type.
+        if method.block.stmts.is_empty() {
+            if let syn::ReturnType::Type(_, ty) = &method.sig.output {
+                method.block = parse_quote_spanned! { ty.span() =>
+                    { ::std::default::Default::default() }
+                };
+                method
+                    .attrs
+                    .push(parse_quote! { #[allow(unused_variables)] });
+            }
         }
         let syn::ImplItemMethod {
             attrs, vis, sig, ..
@@ -76,6 +82,35 @@ impl VirtualMethod {
             mode,
         })
     }
+    /// Async virtual methods are stored as functions returning a non-`Send` boxed future, so
+    /// they can't be used on `sync` types.
+    pub(crate) fn validate_concurrency(
+        methods: &[Self],
+        concurrency: Concurrency,
+        errors: &Errors,
+    ) {
+        if concurrency != Concurrency::None {
+            for async_ in methods.iter().filter_map(|m| m.sig.asyncness.as_ref()) {
+                errors.push_spanned(async_, "async virtual method requires a non-`sync` type");
+            }
+        }
+    }
+    /// The vtable trampolines are nested functions, which can't use the type parameters of the
+    /// type. The methods are dropped so the error isn't followed by unresolved names.
+    pub(crate) fn validate_generics(
+        methods: &mut Vec<Self>,
+        generics: &syn::Generics,
+        errors: &Errors,
+    ) {
+        if generics.type_params().next().is_some() {
+            for method in methods.drain(..) {
+                errors.push_spanned(
+                    &method.sig.ident,
+                    "virtual methods not supported on generic types",
+                );
+            }
+        }
+    }
     fn external_sig(&self) -> syn::Signature {
         let mut sig = self.sig.clone();
         for (index, arg) in sig.inputs.iter_mut().enumerate() {
@@ -126,17 +161,32 @@ impl VirtualMethod {
             },
         };
         let cast_args = self.generic_args.cast_args(&sig, &self.sig, glib);
+        let call = self.awaited(quote! {
+            let #vtable_ident = #get_vtable;
+            let #vtable_ident = #deref_vtable;
+            #cast_args
+            (#vtable_ident.#ident)(#obj_ident, #(#args),*)
+        });
         quote_spanned! { self.sig.span() =>
             #sig {
                 #![inline]
                 let #obj_ident = #glib::Cast::upcast_ref::<#wrapper_ty>(self);
-                let #vtable_ident = #get_vtable;
-                let #vtable_ident = #deref_vtable;
-                #cast_args
-                (#vtable_ident.#ident)(#obj_ident, #(#args),*)
+                #call
             }
         }
     }
+    /// Async vtable entries return a boxed future. The class struct is only borrowed while
+    /// creating it, so it isn't held across the `.await`.
+    fn awaited(&self, call: TokenStream) -> TokenStream {
+        if self.sig.asyncness.is_none() {
+            return call;
+        }
+        let future_ident = syn::Ident::new("____future", Span::mixed_site());
+        quote! {
+            let #future_ident = { #call };
+            #future_ident.await
+        }
+    }
     fn parent_sig(&self, ident: &syn::Ident, glib: &syn::Path) -> syn::Signature {
         let mut sig = self.external_sig();
         sig.ident = format_ident!("parent_{}", self.sig.ident);
@@ -160,10 +210,11 @@ impl VirtualMethod {
         let parent_ident = std::mem::replace(&mut sig.ident, self.sig.ident.clone());
         let external_sig = self.external_sig();
         let args = util::signature_args(&external_sig);
+        let await_ = self.sig.asyncness.as_ref().map(|_| quote! { .await });
         quote_spanned! { self.sig.span() =>
             #sig {
                 #![inline]
-                #ext_trait::#parent_ident(self, #this_ident, #(#args),*)
+                #ext_trait::#parent_ident(self, #this_ident, #(#args),*) #await_
             }
         }
     }
@@ -186,23 +237,29 @@ impl VirtualMethod {
             TypeBase::Class => quote! { parent_class },
             TypeBase::Interface => quote! { parent_interface::<#ty> },
         };
+        let call = self.awaited(quote! {
+            let #vtable_ident = <Self as #glib::subclass::types::ObjectSubclassType>::type_data();
+            let #vtable_ident = unsafe {
+                &*(
+                    #vtable_ident.as_ref().#parent_vtable_method()
+                    as *mut <#ty as #glib::object::ObjectType>::GlibClassType
+                )
+            };
+            (#vtable_ident.#ident)(#(#args),*)
+        });
         quote_spanned! { self.sig.span() =>
             #sig {
                 #![inline]
                 let #this_ident = unsafe {
                     #glib::Cast::unsafe_cast_ref::<#ty>(#this_ident)
                 };
-                let #vtable_ident = <Self as #glib::subclass::types::ObjectSubclassType>::type_data();
-                let #vtable_ident = unsafe {
-                    &*(
-                        #vtable_ident.as_ref().#parent_vtable_method()
-                        as *mut <#ty as #glib::object::ObjectType>::GlibClassType
-                    )
-                };
-                (#vtable_ident.#ident)(#(#args),*)
+                #call
             }
         }
     }
+    fn async_lifetime() -> syn::Lifetime {
+        syn::Lifetime::new("'____async", Span::mixed_site())
+    }
     fn trampoline_sig(&self, ident: syn::Ident, ty: syn::Type) -> syn::Signature {
         let mut sig = self.external_sig();
         match sig.receiver().cloned() {
@@ -224,6 +281,24 @@ impl VirtualMethod {
             }
             _ => {}
         }
+        // Async methods are stored as functions returning a boxed future that borrows all the
+        // reference arguments.
+        if sig.asyncness.take().is_some() {
+            let lifetime = Self::async_lifetime();
+            for arg in &mut sig.inputs {
+                ElidedLifetimes(&lifetime).visit_fn_arg_mut(arg);
+            }
+            let output = match &sig.output {
+                syn::ReturnType::Type(_, ty) => quote! { #ty },
+                syn::ReturnType::Default => quote! { () },
+            };
+            sig.output = parse_quote_spanned! { self.sig.output.span() =>
+                -> ::std::pin::Pin<::std::boxed::Box<
+                    dyn ::std::future::Future<Output = #output> + #lifetime
+                >>
+            };
+            sig.generics.params.insert(0, parse_quote! { #lifetime });
+        }
         sig
     }
     pub(crate) fn vtable_field(&self, wrapper_ty: &syn::Type) -> TokenStream {
@@ -234,10 +309,45 @@ impl VirtualMethod {
             syn::FnArg::Typed(syn::PatType { ty, .. }) => ty.as_ref(),
             _ => unreachable!(),
         });
+        let lifetimes = self.sig.asyncness.as_ref().map(|_| {
+            let lifetime = Self::async_lifetime();
+            quote! { for<#lifetime> }
+        });
         quote_spanned! { self.sig.span() =>
-            #ident: fn(#(#args),*) #output
+            #ident: #lifetimes fn(#(#args),*) #output
         }
     }
+    /// Checks the instance handed to a trampoline before it gets reinterpreted as `ty`, so
+    /// misuse from unsafe code panics instead of being undefined behavior. Expands to nothing
+    /// without `debug_assertions`, so release builds pay no cost.
+    fn debug_check_this(
+        &self,
+        this_ident: &syn::Ident,
+        ty: &TokenStream,
+        glib: &syn::Path,
+    ) -> Option<TokenStream> {
+        let recv = self.sig.receiver()?;
+        let name = self.sig.ident.to_string();
+        let obj_ident = syn::Ident::new("____obj", Span::mixed_site());
+        Some(quote_spanned! { recv.span() =>
+            #[cfg(debug_assertions)]
+            {
+                let #obj_ident = ::std::convert::AsRef::<#glib::Object>::as_ref(&#this_ident);
+                ::std::assert!(
+                    !#glib::ObjectType::as_ptr(#obj_ident).is_null(),
+                    "virtual method `{}` called with a null instance",
+                    #name,
+                );
+                ::std::assert!(
+                    #glib::ObjectExt::is::<#ty>(#obj_ident),
+                    "virtual method `{}` called on `{}`, expected an instance of `{}`",
+                    #name,
+                    #glib::ObjectExt::type_(#obj_ident).name(),
+                    <#ty as #glib::StaticType>::static_type().name(),
+                );
+            }
+        })
+    }
     pub(crate) fn set_default_trampoline(
         &self,
         type_name: &syn::Ident,
@@ -263,11 +373,17 @@ impl VirtualMethod {
             TypeMode::Subclass => quote! { #type_name },
             TypeMode::Wrapper => quote! { super::#type_name },
         };
+        let check_this = self.debug_check_this(&this_ident, &quote! { #ty }, glib);
         let args = util::signature_args(&sig);
+        let mut call = quote! { #type_name::#ident(#(#args),*) };
+        if self.sig.asyncness.is_some() {
+            call = quote! { ::std::boxed::Box::pin(#call) };
+        }
         quote_spanned! { self.sig.span() =>
             #sig {
+                #check_this
                 #unwrap_recv
-                #type_name::#ident(#(#args),*)
+                #call
             }
             #class_ident.#ident = #trampoline_ident;
         }
@@ -290,9 +406,15 @@ impl VirtualMethod {
             #type_ident: #glib::subclass::types::ObjectSubclass + #trait_name
         };
         sig.generics.params.push(param);
+        let check_this = self.debug_check_this(
+            &this_ident,
+            &quote! { <#type_ident as #glib::subclass::types::ObjectSubclass>::Type },
+            glib,
+        );
         let args = util::signature_args(&sig);
         quote_spanned! { self.sig.span() =>
             #sig {
+                #check_this
                 let #this_ident = #glib::Cast::dynamic_cast_ref::<<#type_ident as #glib::subclass::types::ObjectSubclass>::Type>(
                     #this_ident
                 ).unwrap();
@@ -303,3 +425,21 @@ impl VirtualMethod {
         }
     }
 }
+
+/// Names the elided lifetimes of references in an async vtable entry, so they can be tied to the
+/// lifetime of the returned future.
+struct ElidedLifetimes<'a>(&'a syn::Lifetime);
+
+impl<'a> VisitMut for ElidedLifetimes<'a> {
+    fn visit_type_reference_mut(&mut self, ty: &mut syn::TypeReference) {
+        if ty.lifetime.is_none() {
+            ty.lifetime = Some(self.0.clone());
+        }
+        syn::visit_mut::visit_type_reference_mut(self, ty);
+    }
+    fn visit_lifetime_mut(&mut self, lifetime: &mut syn::Lifetime) {
+        if lifetime.ident == "_" {
+            *lifetime = self.0.clone();
+        }
+    }
+}
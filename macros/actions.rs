@@ -1190,7 +1190,7 @@ impl Action {
         let public_method = def
             .inner
             .public_method_mut(handler.mode, &handler.sig.ident)?;
-        self.prepare_public_method(handler, public_method, def.final_, errors);
+        self.prepare_public_method(handler, public_method, def.ext_trait.is_none(), errors);
         public_method.custom_body = Some((
             String::from("#[action]"),
             Box::new(handler.to_public_method_expr(self, sub_ty, wrapper_ty, bind_expr, &go)),
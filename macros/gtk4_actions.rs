@@ -483,7 +483,7 @@ pub(crate) fn extend_widget_actions(def: &mut ClassDefinition, errors: &Errors)
                     ),
                 );
             }
-            action.prepare_public_method(pm, def.final_, errors);
+            action.prepare_public_method(pm, def.ext_trait.is_none(), errors);
             let self_ident = syn::Ident::new("self", Span::mixed_site());
             pm.sig.inputs[0] = parse_quote_spanned! { Span::mixed_site() => &#self_ident };
             let param = action
@@ -512,7 +512,7 @@ pub(crate) fn extend_widget_actions(def: &mut ClassDefinition, errors: &Errors)
         }
     }
     {
-        let class_ident = syn::Ident::new("class", Span::mixed_site());
+        let class_ident = ClassDefinition::class_init_ident();
         let actions = actions.iter().map(|a| a.to_token_stream(&class_ident, &go));
         let property_actions = property_actions
             .iter()
@@ -0,0 +1,137 @@
+use gobject_core::{
+    util::{self, Errors},
+    ClassDefinition, TypeMode,
+};
+use proc_macro2::Span;
+use quote::{format_ident, quote, quote_spanned};
+use syn::{parse_quote, spanned::Spanned};
+
+const BUILDABLE_METHODS: &[&str] = &[
+    "set_id",
+    "id",
+    "add_child",
+    "set_buildable_property",
+    "parser_finished",
+    "internal_child",
+    "construct_child",
+];
+
+fn buildable_sig(name: &str, go: &syn::Path) -> syn::Signature {
+    let ident = format_ident!("{}", name);
+    let obj = quote! { buildable: &<Self as #go::glib::subclass::types::ObjectSubclass>::Type };
+    let builder = quote! { builder: &#go::gtk4::Builder };
+    match name {
+        "set_id" => parse_quote! { fn #ident(&self, #obj, id: &str) },
+        "id" => parse_quote! {
+            fn #ident(&self, #obj) -> ::std::option::Option<#go::glib::GString>
+        },
+        "add_child" => parse_quote! {
+            fn #ident(
+                &self,
+                #obj,
+                #builder,
+                child: &#go::glib::Object,
+                type_: ::std::option::Option<&str>,
+            )
+        },
+        "set_buildable_property" => parse_quote! {
+            fn #ident(&self, #obj, #builder, name: &str, value: &#go::glib::Value)
+        },
+        "parser_finished" => parse_quote! { fn #ident(&self, #obj, #builder) },
+        "internal_child" => parse_quote! {
+            fn #ident(
+                &self,
+                #obj,
+                #builder,
+                name: &str,
+            ) -> ::std::option::Option<#go::glib::Object>
+        },
+        "construct_child" => parse_quote! {
+            fn #ident(&self, #obj, #builder, name: &str) -> #go::glib::Object
+        },
+        _ => unreachable!(),
+    }
+}
+
+/// Methods on the subclass marked `#[buildable]` are forwarded from a generated `BuildableImpl`
+/// and the class is registered as implementing `gtk4::Buildable`, replacing the inherited
+/// implementation. Methods take the same arguments as the trait methods, and anything not
+/// marked chains up to the parent.
+pub(crate) fn extend_buildable(def: &mut ClassDefinition, errors: &Errors) {
+    let mut methods = Vec::<(String, Span)>::new();
+    for impl_ in def.inner.methods_items_mut() {
+        if TypeMode::for_item_type(&impl_.self_ty) != Some(TypeMode::Subclass) {
+            continue;
+        }
+        for item in &mut impl_.items {
+            if let syn::ImplItem::Method(method) = item {
+                if let Some(attrs) = util::extract_attrs(&mut method.attrs, "buildable") {
+                    for attr in &attrs {
+                        if !attr.tokens.is_empty() {
+                            errors.push_spanned(&attr.tokens, "Unexpected arguments");
+                        }
+                    }
+                    let name = method.sig.ident.to_string();
+                    if !BUILDABLE_METHODS.contains(&name.as_str()) {
+                        errors.push_spanned(
+                            &method.sig.ident,
+                            format!(
+                                "Unknown Buildable method `{}`, expected one of: {}",
+                                name,
+                                BUILDABLE_METHODS.join(", ")
+                            ),
+                        );
+                    } else if methods.iter().any(|(n, _)| *n == name) {
+                        errors.push_spanned(
+                            &method.sig.ident,
+                            format!("Duplicate Buildable method `{}`", name),
+                        );
+                    } else {
+                        methods.push((name, method.sig.span()));
+                    }
+                }
+            }
+        }
+    }
+    if methods.is_empty() {
+        return;
+    }
+
+    let go = def.inner.crate_path.clone();
+    let is_buildable = |path: &syn::Path| {
+        path.segments
+            .last()
+            .map(|s| s.ident == "Buildable")
+            .unwrap_or(false)
+    };
+    def.inherits.retain(|path| !is_buildable(path));
+    if !def.implements.iter().any(is_buildable) {
+        def.implements.push(parse_quote! { #go::gtk4::Buildable });
+    }
+
+    let name = &def.inner.name;
+    let head = def.inner.trait_head(
+        &parse_quote! { #name },
+        quote! { #go::gtk4::subclass::prelude::BuildableImpl },
+    );
+    let methods = methods.iter().map(|(method, span)| {
+        let sig = buildable_sig(method, &go);
+        let ident = &sig.ident;
+        let args = util::signature_args(&sig);
+        quote_spanned! { *span =>
+            #sig {
+                Self::#ident(self, #(#args),*)
+            }
+        }
+    });
+    def.inner
+        .module
+        .content
+        .get_or_insert_with(Default::default)
+        .1
+        .push(syn::Item::Verbatim(quote! {
+            #head {
+                #(#methods)*
+            }
+        }));
+}
@@ -318,7 +318,7 @@ impl TemplateCallback {
 
 impl TemplateSource {
     fn to_tokens(&self, go: &syn::Path) -> TokenStream {
-        let class_ident = syn::Ident::new("class", Span::mixed_site());
+        let class_ident = ClassDefinition::class_init_ident();
         match self {
             Self::File(file) => quote_spanned! { file.span() =>
                 #go::gtk4::subclass::widget::WidgetClassSubclassExt::set_template_static(
@@ -449,7 +449,7 @@ pub(crate) fn extend_template(def: &mut ClassDefinition, errors: &Errors) {
 
     let has_callbacks = callbacks.iter().any(|c| c.mode == TypeMode::Subclass);
     let has_instance_callbacks = callbacks.iter().any(|c| c.mode == TypeMode::Wrapper);
-    let class_ident = syn::Ident::new("class", Span::mixed_site());
+    let class_ident = ClassDefinition::class_init_ident();
     let this_ident = syn::Ident::new("obj", Span::mixed_site());
     let widget_ident = syn::Ident::new("_widget", Span::mixed_site());
     let go = def.inner.crate_path.clone();
@@ -100,17 +100,10 @@ fn extend_initable(def: &mut gobject_core::ClassDefinition, errors: &Errors) {
                 TypeMode::Wrapper => &wrapper_ty,
             };
             let construct_try = fallible.then(|| quote! { ? });
-            let init_try = fallible.then(|| quote! { ? }).unwrap_or_else(|| {
-                quote_spanned! { span =>
-                    .unwrap_or_else(|e| {
-                        ::std::panic!(
-                            "Failed to construct {}: {:?}",
-                            <#wrapper_ty as #glib::StaticType>::static_type().name(),
-                            e,
-                        );
-                    })
-                }
-            });
+            let init_try = constructor
+                .on_error()
+                .handler(&wrapper_ty, &glib)
+                .unwrap_or_else(|| quote! { ? });
             let ret = fallible
                 .then(|| quote! { ::std::result::Result::Ok(#obj_ident) })
                 .unwrap_or_else(|| quote! { #obj_ident });
@@ -230,7 +223,7 @@ fn extend_async_initable(def: &mut gobject_core::ClassDefinition, errors: &Error
                 // don't generate another auto constructor
                 pm.constructor = Some(ConstructorType::Custom {
                     default: constructor.is_default(),
-                    fallible: constructor.fallible(),
+                    on_error: constructor.on_error().clone(),
                 });
                 if init_arg_count > 2 {
                     // remove the cancellable argument
@@ -273,17 +266,10 @@ fn extend_async_initable(def: &mut gobject_core::ClassDefinition, errors: &Error
             };
 
             let construct_try = fallible.then(|| quote! { ? });
-            let init_try = fallible.then(|| quote! { ? }).unwrap_or_else(|| {
-                quote_spanned! { span =>
-                    .unwrap_or_else(|e| {
-                        ::std::panic!(
-                            "Failed to construct {}: {:?}",
-                            <#wrapper_ty as #glib::StaticType>::static_type().name(),
-                            e,
-                        );
-                    })
-                }
-            });
+            let init_try = constructor
+                .on_error()
+                .handler(&wrapper_ty, &glib)
+                .unwrap_or_else(|| quote! { ? });
             let ret = fallible
                 .then(|| quote! { ::std::result::Result::Ok(#obj_ident) })
                 .unwrap_or_else(|| quote! { #obj_ident });
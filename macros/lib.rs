@@ -7,6 +7,8 @@ mod actions;
 #[cfg(feature = "gtk4")]
 mod gtk4_actions;
 #[cfg(feature = "gtk4")]
+mod gtk4_buildable;
+#[cfg(feature = "gtk4")]
 mod gtk4_templates;
 #[cfg(any(feature = "gtk4", feature = "gio"))]
 mod initable;
@@ -56,6 +58,8 @@ pub fn class(attr: TokenStream, item: TokenStream) -> TokenStream {
             actions::extend_actions(&mut class, &errors);
             #[cfg(any(feature = "gtk4", feature = "gio"))]
             initable::extend_initables(&mut class, &errors);
+            #[cfg(feature = "gtk4")]
+            gtk4_buildable::extend_buildable(&mut class, &errors);
             #[cfg(feature = "variant")]
             variant::extend_variant(
                 &mut class.inner,
@@ -182,6 +186,7 @@ pub fn gtk4_widget(attr: TokenStream, item: TokenStream) -> TokenStream {
             initable::extend_initables(&mut class, &errors);
             gtk4_templates::extend_template(&mut class, &errors);
             gtk4_actions::extend_widget_actions(&mut class, &errors);
+            gtk4_buildable::extend_buildable(&mut class, &errors);
             class.add_private_items();
             class.to_token_stream()
         })
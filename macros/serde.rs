@@ -150,7 +150,7 @@ pub(crate) fn extend_serde(
                             .unwrap_or_else(|| quote! { #wrapper_ty });
                         Some(quote! { #path::#name })
                     }
-                    PropertyPermission::AllowNoMethod => {
+                    PropertyPermission::AllowNoMethod | PropertyPermission::AllowClosure(_) => {
                         let pname = prop.name.to_string();
                         getters.push(quote! {
                             fn #name(obj: &#wrapper_ty) -> #inner_ty {
@@ -279,7 +279,7 @@ pub(crate) fn extend_variant(
                             .unwrap_or_else(|| quote! { #wrapper_ty });
                         quote! { #path::#name(#self_ident) }
                     }
-                    PropertyPermission::AllowNoMethod => {
+                    PropertyPermission::AllowNoMethod | PropertyPermission::AllowClosure(_) => {
                         let pname = prop.name.to_string();
                         quote! {
                             #glib::prelude::ObjectExt::property(#self_ident, #pname)
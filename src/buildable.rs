@@ -151,3 +151,32 @@ where
 {
     type ParamSpec = T::ParamSpec;
 }
+
+/// Picks the pspec default of an enum property without an explicit default. Calling
+/// `(&EnumDefault::<T>(PhantomData)).enum_default_value()` with both traits in scope resolves
+/// to `T::default()` when `T` implements [`Default`], and falls back to `0` otherwise.
+#[doc(hidden)]
+pub struct EnumDefault<T>(pub std::marker::PhantomData<T>);
+
+#[doc(hidden)]
+pub trait EnumDefaultValue {
+    fn enum_default_value(&self) -> i32;
+}
+impl<T> EnumDefaultValue for EnumDefault<T>
+where
+    T: Default + glib::translate::IntoGlib<GlibType = i32>,
+{
+    fn enum_default_value(&self) -> i32 {
+        T::default().into_glib()
+    }
+}
+
+#[doc(hidden)]
+pub trait EnumFallbackValue {
+    fn enum_default_value(&self) -> i32;
+}
+impl<T> EnumFallbackValue for &EnumDefault<T> {
+    fn enum_default_value(&self) -> i32 {
+        0
+    }
+}
@@ -12,6 +12,7 @@ use glib::{
 use std::{
     cell::RefCell,
     ops::{Deref, DerefMut},
+    sync::{Arc, RwLock},
 };
 
 /// A cell holding an `Option<T>`. This should only be used with boxed/object properties using the
@@ -513,3 +514,76 @@ where
         old != value
     }
 }
+
+/// A cell holding an immutable snapshot of `T` behind an [`Arc`]. Writes swap in
+/// a new `Arc`, so readers holding a previous snapshot are never blocked or affected. Reads take
+/// a read lock only for as long as it takes to clone the `Arc`.
+///
+/// Intended for collection properties read far more often than written; use with
+/// `#[property(snapshot)]` to have the generated getter return the `Arc` directly. Going through
+/// the property system (`ObjectExt::property`, bindings) still clones the whole collection into a
+/// [`Value`].
+#[derive(Debug, Default)]
+pub struct SnapshotCell<T>(RwLock<Arc<T>>);
+
+impl<T> SnapshotCell<T> {
+    pub fn new(value: T) -> Self {
+        Self(RwLock::new(Arc::new(value)))
+    }
+    pub fn snapshot(&self) -> Arc<T> {
+        self.0.read().unwrap().clone()
+    }
+    pub fn replace(&self, value: T) -> Arc<T> {
+        std::mem::replace(&mut *self.0.write().unwrap(), Arc::new(value))
+    }
+}
+impl<T> From<T> for SnapshotCell<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: ParamSpecBuildable> ParamSpecBuildable for SnapshotCell<T> {
+    type ParamSpec = T::ParamSpec;
+}
+impl<T: ValueType> ParamStore for SnapshotCell<T> {
+    type Type = T;
+}
+impl<T> ParamStoreRead for SnapshotCell<T>
+where
+    T: ValueType + Clone,
+{
+    type ReadType = T;
+    fn get_owned(&self) -> Self::ReadType {
+        (*self.snapshot()).clone()
+    }
+    fn get_value(&self) -> Value {
+        self.snapshot().to_value()
+    }
+}
+impl<'a, T> ParamStoreBorrow<'a> for SnapshotCell<T> {
+    type BorrowType = Arc<T>;
+
+    fn borrow(&'a self) -> Self::BorrowType {
+        self.snapshot()
+    }
+}
+impl<'a, T> ParamStoreWrite<'a> for SnapshotCell<T>
+where
+    T: ValueType,
+{
+    type WriteType = T;
+    fn set_owned(&'a self, value: Self::WriteType) {
+        self.replace(value);
+    }
+}
+impl<'a, T> ParamStoreWriteChanged<'a> for SnapshotCell<T>
+where
+    T: ValueType + PartialEq,
+{
+    fn set_owned_checked(&'a self, value: Self::WriteType) -> bool {
+        let changed = *self.snapshot() != value;
+        self.replace(value);
+        changed
+    }
+}
@@ -0,0 +1,42 @@
+use glib::{object::ObjectExt, Cast, IsA, Object};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const LEAK_CHECK_KEY: &str = "gobject-leak-check";
+
+/// Guard attached to instances of classes using `#[class(leak_check)]` in debug builds. It is
+/// stored as object data so it gets dropped when the instance is finalized. The live instance
+/// counter is always atomic so the count stays correct for `sync` classes finalized from other
+/// threads.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct LeakCheck {
+    live: &'static AtomicUsize,
+}
+
+impl LeakCheck {
+    pub fn attach(obj: &impl IsA<Object>, live: &'static AtomicUsize) {
+        live.fetch_add(1, Ordering::SeqCst);
+        let guard = Self { live };
+        unsafe { obj.upcast_ref::<Object>().set_data(LEAK_CHECK_KEY, guard) };
+    }
+    /// Logs a `g_warning` in the `gobject` domain if any instances of `type_name` are still
+    /// alive, and returns how many there are.
+    pub fn report(type_name: &str, live: &AtomicUsize) -> usize {
+        let count = live.load(Ordering::SeqCst);
+        if count > 0 {
+            glib::g_warning!(
+                "gobject",
+                "{} instance(s) of `{}` still alive at leak check",
+                count,
+                type_name
+            );
+        }
+        count
+    }
+}
+
+impl Drop for LeakCheck {
+    fn drop(&mut self) {
+        self.live.fetch_sub(1, Ordering::SeqCst);
+    }
+}
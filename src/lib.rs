@@ -7,6 +7,7 @@
 //! mod obj {
 //!     #[derive(Default)]
 //!     pub struct MyObj {
+//!         /// Doc comments on the field are also copied onto `my_prop` and `set_my_prop`.
 //!         #[property(get, set)]
 //!         my_prop: std::cell::Cell<u64>,
 //!     }
@@ -147,6 +148,9 @@ mod buildable;
 pub use buildable::*;
 mod cells;
 pub use cells::*;
+mod leak_check;
+#[doc(hidden)]
+pub use leak_check::*;
 mod store;
 pub use store::*;
 #[cfg(feature = "use_serde")]
@@ -430,6 +430,25 @@ atomic_type!(std::sync::atomic::AtomicU8, u8);
 atomic_type!(std::sync::atomic::AtomicU32, u32);
 atomic_type!(std::sync::atomic::AtomicU64, u64);
 
+/// Read-only properties are never written after construction, so their fields don't need a cell.
+macro_rules! plain_type {
+    ($($ty:ty),*) => {
+        $(
+            impl ParamStore for $ty {
+                type Type = $ty;
+            }
+            impl ParamStoreRead for $ty {
+                type ReadType = $ty;
+                fn get_owned(&self) -> $ty {
+                    <$ty as Clone>::clone(self)
+                }
+            }
+        )*
+    };
+}
+
+plain_type!(bool, i8, i32, i64, u8, u32, u64, f32, f64, String);
+
 impl<T> ParamStore for std::sync::atomic::AtomicPtr<T> {
     type Type = glib::Pointer;
 }
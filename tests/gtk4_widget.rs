@@ -156,3 +156,65 @@ mod action_widget {
     }
     impl gtk4::subclass::prelude::WidgetImpl for ActionWidget {}
 }
+
+#[gobject::gtk4_widget(final)]
+mod buildable_widget {
+    use gtk4::prelude::*;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    pub struct BuildableWidget {
+        children: RefCell<Vec<String>>,
+    }
+    impl BuildableWidget {
+        #[buildable]
+        fn add_child(
+            &self,
+            buildable: &super::BuildableWidget,
+            _builder: &gtk4::Builder,
+            child: &glib::Object,
+            type_: Option<&str>,
+        ) {
+            let child = child.downcast_ref::<gtk4::Widget>().unwrap();
+            child.set_parent(buildable);
+            self.children.borrow_mut().push(format!(
+                "{}:{}",
+                type_.unwrap_or("none"),
+                child.type_().name()
+            ));
+        }
+        #[public]
+        fn children(&self) -> Vec<String> {
+            self.children.borrow().clone()
+        }
+        fn dispose(&self, obj: &super::BuildableWidget) {
+            while let Some(child) = obj.first_child() {
+                child.unparent();
+            }
+        }
+    }
+    impl gtk4::subclass::prelude::WidgetImpl for BuildableWidget {}
+}
+
+#[gtk4::test]
+fn buildable() {
+    use gtk4::prelude::*;
+
+    BuildableWidget::static_type();
+    let builder = gtk4::Builder::from_string(
+        r#"
+        <interface>
+          <object class="BuildableWidget" id="widget">
+            <child type="start">
+              <object class="GtkLabel"/>
+            </child>
+            <child>
+              <object class="GtkButton"/>
+            </child>
+          </object>
+        </interface>
+        "#,
+    );
+    let widget = builder.object::<BuildableWidget>("widget").unwrap();
+    assert_eq!(widget.children(), &["start:GtkLabel", "none:GtkButton"]);
+}
@@ -1,3 +1,5 @@
+use glib::StaticType;
+
 #[gobject::interface(sync)]
 mod iface {
     use std::marker::PhantomData;
@@ -72,6 +74,87 @@ mod implement2 {
     }
 }
 
+#[gobject::interface]
+mod other_iface {
+    use std::marker::PhantomData;
+    #[derive(Copy, Clone)]
+    pub struct OtherIface {
+        #[property(get, set)]
+        _other_prop: PhantomData<String>,
+    }
+}
+
+#[gobject::class(final, implements(Dummy, OtherIface))]
+mod implement3 {
+    use std::cell::RefCell;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    pub struct Implementor3 {
+        #[property(get, set, override_iface)]
+        my_prop: Mutex<u64>,
+        #[property(get, set, override_iface)]
+        other_prop: RefCell<String>,
+    }
+    impl super::DummyImpl for Implementor3 {}
+    impl super::OtherIfaceImpl for Implementor3 {}
+}
+
+#[gobject::interface]
+mod item_container {
+    #[derive(Copy, Clone)]
+    pub struct ItemContainer {}
+    impl ItemContainer {
+        #[virt]
+        fn item_type(_iface: &super::ItemContainer) -> glib::Type {
+            glib::Type::INVALID
+        }
+    }
+}
+
+#[gobject::class(final, implements(ItemContainer))]
+mod string_container {
+    use glib::StaticType;
+
+    #[derive(Default)]
+    pub struct StringContainer {}
+    impl super::ItemContainerImpl for StringContainer {
+        fn item_type(&self, _obj: &Self::Type) -> glib::Type {
+            String::static_type()
+        }
+    }
+}
+
+#[gobject::interface]
+mod generic_iface {
+    use glib::StaticType;
+    use std::marker::PhantomData;
+    #[derive(Copy, Clone)]
+    pub struct GenericIface<T: StaticType + Copy + 'static> {
+        #[property(get, set)]
+        _count: PhantomData<u32>,
+        _item: PhantomData<T>,
+    }
+    impl<T: StaticType + Copy + 'static> super::GenericIface<T> {
+        #[public]
+        fn item_type(&self) -> glib::Type {
+            T::static_type()
+        }
+    }
+}
+
+#[gobject::class(final, implements("GenericIface<u32>"))]
+mod u32_items {
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    pub struct U32Items {
+        #[property(get, set, override_iface)]
+        count: Cell<u32>,
+    }
+    impl super::GenericIfaceImpl for U32Items {}
+}
+
 #[test]
 fn interface() {
     use std::sync::Arc;
@@ -105,3 +188,82 @@ fn interface() {
 
     assert_eq!(*called_signals.lock().unwrap(), &["my"]);
 }
+
+#[test]
+fn inferred_interface_overrides() {
+    let obj = glib::Object::new::<Implementor3>(&[]).unwrap();
+    obj.set_my_prop(5);
+    obj.set_other_prop("hello".into());
+    assert_eq!(obj.my_prop(), 5);
+    assert_eq!(obj.other_prop(), "hello");
+}
+
+#[test]
+fn item_type_interface() {
+    use glib::StaticType;
+
+    let obj = glib::Object::new::<StringContainer>(&[]).unwrap();
+    assert_eq!(obj.item_type(), String::static_type());
+}
+
+#[gobject::class]
+mod base_obj {
+    #[derive(Default)]
+    pub struct BaseObj {}
+    impl BaseObj {
+        #[public]
+        fn base_name(&self) -> String {
+            "base".into()
+        }
+    }
+}
+
+#[gobject::interface(requires(BaseObj))]
+mod needs_base {
+    #[derive(Copy, Clone)]
+    pub struct NeedsBase {}
+}
+
+#[gobject::class(
+    final,
+    extends(BaseObj),
+    implements(NeedsBase),
+    parent_trait = "super::BaseObjImpl"
+)]
+mod base_implementor {
+    #[derive(Default)]
+    pub struct BaseImplementor {}
+    impl super::BaseObjImpl for BaseImplementor {}
+    impl super::NeedsBaseImpl for BaseImplementor {}
+}
+
+#[test]
+fn interface_prerequisites() {
+    use glib::{Cast, StaticType};
+
+    assert_eq!(
+        &*NeedsBase::static_type().interface_prerequisites(),
+        &[BaseObj::static_type()]
+    );
+    let obj = glib::Object::new::<BaseImplementor>(&[]).unwrap();
+    let iface = obj.upcast_ref::<NeedsBase>();
+    assert_eq!(iface.base_name(), "base");
+}
+
+#[test]
+fn upcast_interfaces() {
+    let obj = glib::Object::new::<Implementor3>(&[]).unwrap();
+    obj.set_my_prop(5);
+    assert_eq!(obj.upcast_dummy().my_prop(), 5);
+    obj.upcast_other_iface().set_other_prop("hello".into());
+    assert_eq!(obj.other_prop(), "hello");
+}
+
+#[test]
+fn generic_interface() {
+    let obj = glib::Object::new::<U32Items>(&[("count", &3u32)]).unwrap();
+    assert_eq!(obj.item_type(), glib::Type::U32);
+    assert_eq!(obj.count(), 3);
+    let iface: &GenericIface<u32> = glib::Cast::upcast_ref(&obj);
+    assert_eq!(iface.item_type(), glib::Type::U32);
+}
@@ -18,6 +18,63 @@ fn object_final() {
     obj.emit_abc();
 }
 
+#[gobject::class(final, leak_check)]
+mod obj_leak_check {
+    #[derive(Default)]
+    pub struct ObjLeakCheck {}
+}
+
+#[test]
+fn object_leak_check() {
+    let obj = glib::Object::new::<ObjLeakCheck>(&[]).unwrap();
+    let obj2 = glib::Object::new::<ObjLeakCheck>(&[]).unwrap();
+    if cfg!(debug_assertions) {
+        assert_eq!(ObjLeakCheck::live_instance_count(), 2);
+    }
+    drop(obj);
+    drop(obj2);
+    assert_eq!(ObjLeakCheck::live_instance_count(), 0);
+}
+
+#[gobject::class(final, leak_check)]
+mod obj_leak_report {
+    #[derive(Default)]
+    pub struct ObjLeakReport {}
+}
+
+#[test]
+fn object_leak_check_report() {
+    use std::sync::{Arc, Mutex};
+
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    let handler = glib::log_set_handler(
+        Some("gobject"),
+        glib::LogLevels::LEVEL_WARNING,
+        false,
+        false,
+        {
+            let messages = messages.clone();
+            move |_, _, message| messages.lock().unwrap().push(message.to_owned())
+        },
+    );
+    let obj = glib::Object::new::<ObjLeakReport>(&[]).unwrap();
+    let leaked = ObjLeakReport::check_leaks();
+    drop(obj);
+    assert_eq!(ObjLeakReport::check_leaks(), 0);
+    glib::log_remove_handler(Some("gobject"), handler);
+    let messages = messages.lock().unwrap();
+    if cfg!(debug_assertions) {
+        assert_eq!(leaked, 1);
+        assert_eq!(
+            *messages,
+            ["1 instance(s) of `ObjLeakReport` still alive at leak check"]
+        );
+    } else {
+        assert_eq!(leaked, 0);
+        assert!(messages.is_empty());
+    }
+}
+
 #[gobject::class]
 mod obj_derivable {
     #[derive(Default)]
@@ -36,6 +93,12 @@ mod obj_derivable {
         pub fn with_prop_plus_one(my_prop: u64) -> Self {
             Self::new(my_prop + 1).unwrap()
         }
+        #[constructor(on_error = "propagate")]
+        pub fn try_with_prop(my_prop: u64) -> Result<Self, glib::BoolError> {}
+        #[constructor(on_error = "unwrap")]
+        pub fn unwrap_with_prop(my_prop: u64) -> Self {}
+        #[constructor(on_error(expect = "failed to create ObjDerivable"))]
+        pub fn expect_with_prop(my_prop: u64) -> Self {}
     }
 }
 
@@ -51,6 +114,10 @@ fn object_derivable() {
 
     let obj = ObjDerivable::with_prop_plus_one(99);
     assert_eq!(obj.my_prop(), 100);
+
+    assert_eq!(ObjDerivable::try_with_prop(1).unwrap().my_prop(), 1);
+    assert_eq!(ObjDerivable::unwrap_with_prop(2).my_prop(), 2);
+    assert_eq!(ObjDerivable::expect_with_prop(3).my_prop(), 3);
 }
 
 #[gobject::class]
@@ -211,6 +278,12 @@ mod objects {
         pub(in super::super) struct ObjVis4 {}
         impl super::IfaceVisImpl for ObjVis4 {}
     }
+    #[gobject::class(final, extends(ObjVis4), wrapper_extra(@implements IfaceVis))]
+    mod obj_vis5 {
+        #[derive(Default)]
+        pub(in super::super) struct ObjVis5 {}
+        impl super::ObjVis4Impl for ObjVis5 {}
+    }
 }
 
 #[test]
@@ -219,6 +292,8 @@ fn visibility() {
     glib::Object::new::<objects::ObjVis2>(&[]).unwrap();
     let obj = glib::Object::new::<objects::ObjVis4>(&[]).unwrap();
     glib::Cast::upcast::<objects::IfaceVis>(obj);
+    let obj = glib::Object::new::<objects::ObjVis5>(&[]).unwrap();
+    glib::Cast::upcast::<objects::IfaceVis>(obj);
 }
 
 #[gobject::class]
@@ -322,3 +397,497 @@ mod public_methods_final {
     }
     impl super::PublicMethodsImpl for PublicMethodsFinal {}
 }
+
+#[gobject::class(final, extends("PublicMethods"))]
+mod quoted_parent {
+    #[derive(Default)]
+    pub struct QuotedParent {}
+    impl super::PublicMethodsImpl for QuotedParent {}
+}
+
+#[test]
+fn quoted_parent_path() {
+    let obj = glib::Object::new::<QuotedParent>(&[]).unwrap();
+    obj.set_number(5);
+    let parent: &PublicMethods = glib::Cast::upcast_ref(&obj);
+    assert_eq!(parent.get_number(), 5);
+}
+
+#[gobject::class(no_ext_trait)]
+mod no_ext {
+    #[derive(Default)]
+    pub struct NoExt {
+        #[property(get, set)]
+        my_prop: std::cell::Cell<u64>,
+    }
+    impl NoExt {
+        #[public]
+        fn doubled(&self) -> u64 {
+            self.my_prop.get() * 2
+        }
+    }
+}
+
+#[gobject::class(final, extends(NoExt))]
+mod no_ext_child {
+    #[derive(Default)]
+    pub struct NoExtChild {}
+    impl super::NoExtImpl for NoExtChild {}
+}
+
+#[test]
+fn no_ext_trait() {
+    let obj = glib::Object::new::<NoExt>(&[]).unwrap();
+    obj.set_my_prop(4);
+    assert_eq!(obj.doubled(), 8);
+
+    let child = glib::Object::new::<NoExtChild>(&[]).unwrap();
+    let parent: &NoExt = glib::Cast::upcast_ref(&child);
+    parent.set_my_prop(5);
+    assert_eq!(parent.doubled(), 10);
+}
+
+#[test]
+fn inherent_static_type() {
+    assert_eq!(NoExt::static_type().name(), "NoExt");
+    assert!(NoExtChild::static_type().is_a(NoExt::static_type()));
+}
+
+#[gobject::class(final)]
+mod obj_custom_static_type {
+    #[derive(Default)]
+    pub struct CustomStaticType {}
+    impl super::CustomStaticType {
+        pub fn static_type() -> &'static str {
+            "custom"
+        }
+    }
+}
+
+#[test]
+fn custom_static_type() {
+    assert_eq!(CustomStaticType::static_type(), "custom");
+    assert_eq!(
+        <CustomStaticType as glib::StaticType>::static_type().name(),
+        "CustomStaticType"
+    );
+}
+
+pub trait Greeter {
+    fn greet(&self, name: &str) -> String;
+}
+
+struct Polite;
+impl Greeter for Polite {
+    fn greet(&self, name: &str) -> String {
+        format!("Hello, {}.", name)
+    }
+}
+
+struct Loud;
+impl Greeter for Loud {
+    fn greet(&self, name: &str) -> String {
+        format!("HEY {}!", name.to_uppercase())
+    }
+}
+
+#[gobject::class(final)]
+mod delegating {
+    use std::cell::RefCell;
+
+    pub struct Delegating {
+        greeter: RefCell<Box<dyn super::Greeter>>,
+    }
+    impl Default for Delegating {
+        fn default() -> Self {
+            Self {
+                greeter: RefCell::new(Box::new(super::Polite)),
+            }
+        }
+    }
+    impl Delegating {
+        #[public(delegate = "self.greeter")]
+        fn greet(&self, name: &str) -> String {}
+        #[public]
+        fn set_loud(&self) {
+            self.greeter.replace(Box::new(super::Loud));
+        }
+    }
+}
+
+#[test]
+fn delegate_method() {
+    let obj = glib::Object::new::<Delegating>(&[]).unwrap();
+    assert_eq!(obj.greet("Ann"), "Hello, Ann.");
+    obj.set_loud();
+    assert_eq!(obj.greet("Ann"), "HEY ANN!");
+}
+
+static REGISTER_CALLS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+fn register_counted<T: glib::subclass::types::ObjectSubclass>() -> glib::Type {
+    REGISTER_CALLS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    glib::subclass::register_type::<T>()
+}
+
+#[gobject::class(final, register_fn = "super::register_counted")]
+mod custom_register {
+    #[derive(Default)]
+    pub struct CustomRegister {
+        #[property(get, set)]
+        my_prop: std::cell::Cell<u64>,
+    }
+}
+
+#[test]
+fn custom_register_fn() {
+    let obj = glib::Object::new::<CustomRegister>(&[("my-prop", &3u64)]).unwrap();
+    let obj2 = glib::Object::new::<CustomRegister>(&[]).unwrap();
+    assert_eq!(obj.my_prop(), 3);
+    assert_eq!(obj2.my_prop(), 0);
+    assert_eq!(
+        <CustomRegister as glib::StaticType>::static_type().name(),
+        "CustomRegister"
+    );
+    assert_eq!(REGISTER_CALLS.load(std::sync::atomic::Ordering::SeqCst), 1);
+}
+
+#[derive(Clone, Debug, Default, PartialEq, glib::Boxed)]
+#[boxed_type(name = "TestTransformColor")]
+pub struct Color(u8, u8, u8);
+
+#[gobject::class(final)]
+mod palette {
+    use super::Color;
+
+    #[derive(Default)]
+    pub struct Palette {
+        #[property(get, set, boxed)]
+        color: std::cell::RefCell<Color>,
+    }
+    impl Palette {
+        #[transform]
+        fn color_to_string(color: &Color) -> String {
+            format!("#{:02x}{:02x}{:02x}", color.0, color.1, color.2)
+        }
+        #[transform]
+        fn color_from_string(s: &str) -> Color {
+            let channel = |i: usize| u8::from_str_radix(&s[i..i + 2], 16).unwrap_or(0);
+            Color(channel(1), channel(3), channel(5))
+        }
+    }
+}
+
+#[test]
+fn value_transforms() {
+    use glib::{prelude::ObjectExt, ToValue};
+
+    let obj = glib::Object::new::<Palette>(&[]).unwrap();
+    obj.set_color(Color(0xff, 0x80, 0x00));
+    let value = obj.property_value("color");
+    let string = value.transform::<String>().unwrap();
+    assert_eq!(string.get::<String>().unwrap(), "#ff8000");
+
+    let color = "#102030".to_value().transform::<Color>().unwrap();
+    assert_eq!(color.get::<Color>().unwrap(), Color(0x10, 0x20, 0x30));
+}
+
+#[gobject::class]
+mod container {
+    #[derive(Default)]
+    pub struct Container {
+        pub(super) children: std::cell::RefCell<Vec<glib::Object>>,
+    }
+    impl Container {
+        #[public]
+        fn add<T: glib::IsA<glib::Object>>(&self, child: &T) {
+            self.children
+                .borrow_mut()
+                .push(glib::Cast::upcast(child.clone()));
+        }
+        #[public]
+        fn add_all(&self, children: &[impl glib::IsA<glib::Object>]) {
+            for child in children {
+                self.add(child);
+            }
+        }
+        #[public]
+        fn contains<T>(&self, child: &T) -> bool
+        where
+            T: glib::IsA<glib::Object>,
+        {
+            let child = glib::Cast::upcast_ref::<glib::Object>(child);
+            self.children.borrow().iter().any(|c| c == child)
+        }
+    }
+}
+
+#[gobject::class(final)]
+mod container_final {
+    #[derive(Default)]
+    pub struct ContainerFinal {
+        pub(super) count: std::cell::Cell<usize>,
+    }
+    impl ContainerFinal {
+        #[public]
+        fn add(&self, _child: &impl glib::IsA<glib::Object>) {
+            self.count.set(self.count.get() + 1);
+        }
+    }
+}
+
+#[test]
+fn generic_public_methods() {
+    use glib::subclass::prelude::ObjectSubclassIsExt;
+
+    let obj = glib::Object::new::<Container>(&[]).unwrap();
+    let child = glib::Object::new::<glib::Object>(&[]).unwrap();
+    obj.add(&child);
+    assert!(obj.contains(&child));
+
+    let last = glib::Object::new::<ContainerFinal>(&[]).unwrap();
+    last.add(&child);
+    last.add(&obj);
+    assert_eq!(last.imp().count.get(), 2);
+    obj.add_all(&[last.clone()]);
+    assert!(obj.contains(&last));
+    assert_eq!(obj.imp().children.borrow().len(), 2);
+}
+
+// A custom wrapper written with `glib::wrapper!` gets `Downgrade` from that macro, and its
+// `WeakRef` implements `Upgrade`, so `glib::clone!` weak captures work without extra impls.
+glib::wrapper! {
+    pub struct CustomWrapper(ObjectSubclass<custom_wrapper::CustomWrapper>);
+}
+
+#[gobject::class(final, wrapper = false)]
+mod custom_wrapper {
+    #[derive(Default)]
+    pub struct CustomWrapper {
+        #[property(get, set)]
+        count: std::cell::Cell<u32>,
+    }
+}
+
+#[test]
+fn custom_wrapper_weak_clone() {
+    let obj = glib::Object::new::<CustomWrapper>(&[]).unwrap();
+    let bump = glib::clone!(@weak obj => @default-return false, move || {
+        obj.set_count(obj.count() + 1);
+        true
+    });
+    assert!(bump());
+    assert_eq!(obj.count(), 1);
+    drop(obj);
+    assert!(!bump());
+}
+
+#[gobject::class(final, ord_by = "priority")]
+mod task {
+    #[derive(Default)]
+    pub struct Task {
+        #[property(get, set)]
+        priority: std::cell::Cell<i32>,
+        #[property(get, set)]
+        label: std::cell::RefCell<String>,
+    }
+}
+
+#[test]
+fn ord_by_property() {
+    let task = |priority: i32, label: &str| {
+        glib::Object::new::<Task>(&[("priority", &priority), ("label", &label)]).unwrap()
+    };
+    let mut tasks = vec![task(3, "c"), task(1, "a"), task(3, "d"), task(2, "b")];
+    tasks.sort_by(Task::cmp_by_priority);
+    let labels = tasks.iter().map(|t| t.label()).collect::<Vec<_>>();
+    assert_eq!(labels, ["a", "b", "c", "d"]);
+    assert_eq!(
+        tasks[2].cmp_by_priority(&tasks[3]),
+        std::cmp::Ordering::Equal
+    );
+}
+
+static FINALIZE_LOG: std::sync::Mutex<Vec<&'static str>> = std::sync::Mutex::new(Vec::new());
+
+#[gobject::class(final)]
+mod finalized {
+    #[derive(Default)]
+    pub struct Finalized {}
+    impl Finalized {
+        fn dispose(&self, _obj: &super::Finalized) {
+            super::FINALIZE_LOG.lock().unwrap().push("dispose");
+        }
+        fn finalize(&self) {
+            super::FINALIZE_LOG.lock().unwrap().push("finalize");
+        }
+    }
+}
+
+#[test]
+fn finalize_method() {
+    let obj = glib::Object::new::<Finalized>(&[]).unwrap();
+    assert!(FINALIZE_LOG.lock().unwrap().is_empty());
+    drop(obj);
+    assert_eq!(*FINALIZE_LOG.lock().unwrap(), ["dispose", "finalize"]);
+}
+
+#[gobject::class(final, gtype_name = "MyExact_Name")]
+mod exact_name {
+    #[derive(Default)]
+    pub struct ExactName {}
+}
+
+#[test]
+fn exact_gtype_name() {
+    assert_eq!(ExactName::static_type().name(), "MyExact_Name");
+}
+
+#[gobject::class(final, derive_default)]
+mod obj_default {
+    #[derive(Default)]
+    pub struct ObjDefault {
+        #[property(get, set, default = 7)]
+        count: std::cell::Cell<u32>,
+    }
+}
+
+#[test]
+fn derive_default() {
+    let obj = ObjDefault::default();
+    assert_eq!(obj.count(), 7);
+}
+
+#[gobject::class(final, builder)]
+mod obj_builder {
+    #[derive(Default)]
+    pub struct ObjBuilder {
+        #[property(get, construct_only)]
+        id: std::cell::Cell<u64>,
+        #[property(get, construct_only, default = "unnamed")]
+        label: std::cell::RefCell<String>,
+        #[property(get, set)]
+        count: std::cell::Cell<i32>,
+        #[property(get)]
+        read_only: std::cell::Cell<bool>,
+    }
+}
+
+#[test]
+fn builder() {
+    let obj = ObjBuilder::builder(5).count(3).build();
+    assert_eq!(obj.id(), 5);
+    assert_eq!(obj.label(), "unnamed");
+    assert_eq!(obj.count(), 3);
+    let obj = ObjBuilderBuilder::new(6).label("named".into()).build();
+    assert_eq!(obj.id(), 6);
+    assert_eq!(obj.label(), "named");
+}
+
+#[gobject::class]
+mod obj_receivers {
+    use std::pin::Pin;
+
+    #[derive(Default)]
+    pub struct ObjReceivers {
+        #[property(get, set)]
+        value: std::cell::Cell<u32>,
+    }
+    impl ObjReceivers {
+        #[public]
+        fn typed_ref(self: &Self) -> u32 {
+            self.value.get()
+        }
+        #[public]
+        fn pinned(self: Pin<&Self>) -> u32 {
+            self.value.get() + 1
+        }
+        #[public]
+        fn associated() -> u32 {
+            3
+        }
+    }
+    impl super::ObjReceivers {
+        #[public]
+        fn pinned_wrapper(self: Pin<&Self>) -> u32 {
+            self.value() + 2
+        }
+        #[public]
+        fn into_value(self) -> u32 {
+            self.value()
+        }
+    }
+}
+
+#[test]
+fn public_method_receivers() {
+    let obj = glib::Object::new::<ObjReceivers>(&[]).unwrap();
+    obj.set_value(10);
+    assert_eq!(obj.typed_ref(), 10);
+    assert_eq!(std::pin::Pin::new(&obj).pinned(), 11);
+    assert_eq!(std::pin::Pin::new(&obj).pinned_wrapper(), 12);
+    assert_eq!(ObjReceivers::associated(), 3);
+    assert_eq!(obj.clone().into_value(), 10);
+}
+
+#[test]
+fn constructor_return_type_errors() {
+    use gobject_core::{util::Errors, ClassDefinition, ClassOptions};
+    use syn::parse_quote;
+
+    let check = |ctor: syn::ImplItemMethod| {
+        let errors = Errors::new();
+        let opts = ClassOptions::parse(quote::quote! { final }, &errors);
+        let module = parse_quote! {
+            mod ctor {
+                #[derive(Default)]
+                pub struct Ctor {
+                    #[property(get, set)]
+                    value: std::cell::Cell<u32>,
+                }
+                impl super::Ctor {
+                    #ctor
+                }
+            }
+        };
+        ClassDefinition::parse(module, opts, parse_quote!(gobject), &errors);
+        errors.into_compile_errors().map(|e| e.to_string())
+    };
+
+    let errors = check(parse_quote! {
+        #[constructor]
+        pub fn new(value: u32) -> Self {}
+    })
+    .unwrap();
+    assert!(
+        errors.contains("Fallible constructor must return a `Result`"),
+        "{}",
+        errors
+    );
+    let errors = check(parse_quote! {
+        #[constructor(infallible, default)]
+        pub fn new(value: u32) -> Result<Self, glib::BoolError> {
+            unimplemented!()
+        }
+    })
+    .unwrap();
+    assert!(errors.contains("can't return a `Result`"), "{}", errors);
+    let errors = check(parse_quote! {
+        #[constructor(on_error = "unwrap")]
+        pub fn new(value: u32) -> Result<Self, glib::BoolError> {}
+    })
+    .unwrap();
+    assert!(errors.contains("can't return a `Result`"), "{}", errors);
+    assert!(check(parse_quote! {
+        #[constructor]
+        pub fn new(value: u32) -> Result<Self, glib::BoolError> {}
+    })
+    .is_none());
+    assert!(check(parse_quote! {
+        #[constructor(infallible, default)]
+        pub fn new(value: u32) -> Self {
+            unimplemented!()
+        }
+    })
+    .is_none());
+}
@@ -121,6 +121,17 @@ fn basic_properties() {
     assert_eq!(props.my_construct_only(), 100.0);
 }
 
+#[test]
+fn nick_and_blurb() {
+    let props = glib::Object::new::<BasicProps>(&[]).unwrap();
+    let pspec = props.find_property("my-u8").unwrap();
+    assert_eq!(pspec.nick(), "My U8");
+    assert_eq!(pspec.blurb(), "A uint8");
+    let pspec = props.find_property("my-i32").unwrap();
+    assert_eq!(pspec.nick(), "my-i32");
+    assert_eq!(pspec.blurb(), "my-i32");
+}
+
 #[gobject::class(abstract)]
 mod base {
     use std::marker::PhantomData;
@@ -242,6 +253,24 @@ fn complex_properties() {
     assert!(obj.weak_obj().is_none());
 }
 
+#[test]
+fn enum_and_flags_properties() {
+    let dummy = glib::Object::new::<SmallObject>(&[]).unwrap();
+    let obj = glib::Object::new::<ComplexProps>(&[("construct-obj", &dummy)]).unwrap();
+    let pspec = obj.find_property("animal").unwrap();
+    let pspec = pspec.downcast::<glib::ParamSpecEnum>().unwrap();
+    assert_eq!(pspec.value_type(), Animal::static_type());
+    let pspec = obj.find_property("binding-flags").unwrap();
+    let pspec = pspec.downcast::<glib::ParamSpecFlags>().unwrap();
+    assert_eq!(pspec.value_type(), glib::BindingFlags::static_type());
+
+    assert_eq!(obj.animal(), Animal::Dog);
+    obj.set_animal(Animal::Cat);
+    assert_eq!(obj.property::<Animal>("animal"), Animal::Cat);
+    obj.set_property("binding-flags", glib::BindingFlags::SYNC_CREATE);
+    assert_eq!(obj.binding_flags(), glib::BindingFlags::SYNC_CREATE);
+}
+
 #[gobject::class(final)]
 mod my_obj {
     use glib::StaticType;
@@ -341,3 +370,1164 @@ fn optional_props() {
     obj.set_point(None);
     assert!(obj.point().is_none());
 }
+
+#[gobject::class(final)]
+mod no_accessors {
+    use glib::prelude::*;
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    pub struct NoAccessors {
+        #[property(get, set, no_accessors)]
+        count: Cell<u32>,
+    }
+    impl super::NoAccessors {
+        #[public]
+        pub fn count(&self) -> u32 {
+            self.property::<u32>("count") * 2
+        }
+        #[public]
+        pub fn set_count(&self, count: u32) {
+            self.set_property("count", count / 2);
+        }
+    }
+}
+
+#[test]
+fn no_accessors() {
+    let obj = glib::Object::new::<NoAccessors>(&[]).unwrap();
+    obj.set_count(10);
+    assert_eq!(obj.property::<u32>("count"), 5);
+    assert_eq!(obj.count(), 10);
+    obj.set_property("count", 7u32);
+    assert_eq!(obj.count(), 14);
+}
+
+#[gobject::class(final, sync)]
+mod atomics {
+    use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU32};
+
+    #[derive(Default)]
+    pub struct AtomicProps {
+        #[property(get, set, atomic)]
+        counter: AtomicU32,
+        #[property(get, set, atomic = "Relaxed", explicit_notify, lax_validation)]
+        enabled: AtomicBool,
+        #[property(get, set, atomic = "AcqRel", lax_validation)]
+        offset: AtomicI64,
+    }
+}
+
+#[test]
+fn atomic_props() {
+    let obj = glib::Object::new::<AtomicProps>(&[]).unwrap();
+    let notified = std::rc::Rc::new(std::cell::Cell::new(0));
+    obj.connect_enabled_notify_local(glib::clone!(@strong notified => move |_| {
+        notified.set(notified.get() + 1);
+    }));
+    obj.set_counter(3);
+    assert_eq!(obj.counter(), 3);
+    assert_eq!(obj.property::<u32>("counter"), 3);
+    obj.set_enabled(true);
+    obj.set_enabled(true);
+    assert!(obj.enabled());
+    assert_eq!(notified.get(), 1);
+    obj.set_property("offset", -20i64);
+    assert_eq!(obj.offset(), -20);
+}
+
+#[test]
+fn property_by_name() {
+    let obj = glib::Object::new::<BasicProps>(&[]).unwrap();
+    obj.set_my_i32(12);
+    let value = obj.get_property_by_name("my-i32").unwrap();
+    assert_eq!(value.get::<i32>().unwrap(), 12);
+    assert!(obj.get_property_by_name("writable-i32").is_none());
+    assert!(obj.get_property_by_name("does-not-exist").is_none());
+
+    obj.set_property_by_name("my-i32", &20i32.to_value())
+        .unwrap();
+    assert_eq!(obj.my_i32(), 20);
+    assert!(obj
+        .set_property_by_name("readable-i32", &1i32.to_value())
+        .is_err());
+    assert!(obj
+        .set_property_by_name("my-construct-only", &1.0f64.to_value())
+        .is_err());
+    assert!(obj.set_property_by_name("my-i32", &"x".to_value()).is_err());
+    let err = obj
+        .set_property_by_name("does-not-exist", &1i32.to_value())
+        .unwrap_err();
+    assert!(err.to_string().contains("not found"), "{}", err);
+}
+
+#[gobject::class(final)]
+mod custom_property_by_name {
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    pub struct CustomPropertyByName {
+        #[property(get, set)]
+        value: Cell<i32>,
+    }
+    impl super::CustomPropertyByName {
+        pub fn get_property_by_name(&self, name: &str) -> &'static str {
+            if name == "value" {
+                "custom"
+            } else {
+                "unknown"
+            }
+        }
+    }
+}
+
+#[test]
+fn custom_property_by_name() {
+    let obj = glib::Object::new::<CustomPropertyByName>(&[]).unwrap();
+    assert_eq!(obj.get_property_by_name("value"), "custom");
+    obj.set_property_by_name("value", &3i32.to_value()).unwrap();
+    assert_eq!(obj.value(), 3);
+}
+
+#[test]
+fn bulk_set_properties() {
+    let obj = glib::Object::new::<BasicProps>(&[]).unwrap();
+    let notified = std::rc::Rc::new(std::cell::Cell::new(0));
+    obj.connect_my_i32_notify(glib::clone!(@strong notified => move |obj| {
+        assert_eq!(obj.my_str(), "batch");
+        notified.set(notified.get() + 1);
+    }));
+    obj.try_set_properties_frozen(&[("my-i32", &5i32), ("my-str", &"batch")])
+        .unwrap();
+    assert_eq!(obj.my_i32(), 5);
+    assert_eq!(notified.get(), 1);
+
+    assert!(obj
+        .try_set_properties_frozen(&[("my-i32", &6i32), ("does-not-exist", &1i32)])
+        .is_err());
+    assert_eq!(obj.my_i32(), 5);
+    assert_eq!(notified.get(), 1);
+}
+
+#[test]
+fn bulk_set_properties_unknown_name() {
+    let obj = glib::Object::new::<BasicProps>(&[]).unwrap();
+    obj.set_my_i32(5);
+    let err = obj
+        .try_set_properties_frozen(&[("my-i32", &6i32), ("does-not-exist", &1i32)])
+        .unwrap_err();
+    assert!(err.to_string().contains("'does-not-exist'"), "{}", err);
+    assert!(err.to_string().contains("not found"), "{}", err);
+    let err = obj
+        .try_set_properties_frozen(&[("my-i32", &6i32), ("readable-i32", &1i32)])
+        .unwrap_err();
+    assert!(err.to_string().contains("not writable"), "{}", err);
+    assert_eq!(obj.my_i32(), 5);
+}
+
+#[test]
+fn connect_properties_notify() {
+    let obj = glib::Object::new::<BasicProps>(&[]).unwrap();
+    let notified = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    let ids = obj.connect_properties_notify(
+        &["my-i32", "my-str"],
+        glib::clone!(@strong notified => move |_, pspec| {
+            notified.borrow_mut().push(pspec.name().to_owned());
+        }),
+    );
+    assert_eq!(ids.len(), 2);
+    obj.set_my_i32(5);
+    obj.set_my_mutex(5);
+    assert_eq!(*notified.borrow(), &["my-i32"]);
+    obj.try_set_properties_frozen(&[("my-i32", &6i32), ("my-str", &"batch")])
+        .unwrap();
+    assert_eq!(notified.borrow().len(), 3);
+}
+
+#[test]
+#[should_panic]
+fn connect_properties_notify_unknown() {
+    let obj = glib::Object::new::<BasicProps>(&[]).unwrap();
+    obj.connect_properties_notify(&["does-not-exist"], |_, _| {});
+}
+
+#[gobject::class(final, extends(Optionals))]
+mod optionals_child {
+    use std::cell::Cell;
+    #[derive(Default)]
+    pub struct OptionalsChild {
+        #[property(get, set)]
+        count: Cell<u32>,
+    }
+    impl super::OptionalsImpl for OptionalsChild {}
+}
+
+#[test]
+fn connect_inherited_properties_notify() {
+    let obj = glib::Object::new::<OptionalsChild>(&[]).unwrap();
+    let notified = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    obj.connect_properties_notify(
+        &["count", "point"],
+        glib::clone!(@strong notified => move |_, pspec| {
+            notified.borrow_mut().push(pspec.name().to_owned());
+        }),
+    );
+    obj.set_count(1);
+    obj.upcast_ref::<Optionals>()
+        .set_point(Some(Point { x: 1., y: 2. }));
+    assert_eq!(*notified.borrow(), &["count", "point"]);
+}
+
+#[gobject::class(final)]
+mod closure_props {
+    use std::cell::Cell;
+    use std::marker::PhantomData;
+
+    #[derive(Default)]
+    pub struct ClosureProps {
+        #[property(get, set)]
+        celsius: Cell<f64>,
+        #[property(
+            computed,
+            get = "|this| this.celsius.get() * 9.0 / 5.0 + 32.0",
+            set = "|this, value| this.celsius.set((value - 32.0) * 5.0 / 9.0)"
+        )]
+        fahrenheit: PhantomData<f64>,
+        #[property(
+            get = "|this| this.clamped.get()",
+            set = "|this, value: u8| this.clamped.set(value.min(10))"
+        )]
+        clamped: Cell<u8>,
+    }
+}
+
+#[test]
+fn closure_accessors() {
+    let obj = glib::Object::new::<ClosureProps>(&[]).unwrap();
+    obj.set_celsius(100.0);
+    assert_eq!(obj.fahrenheit(), 212.0);
+    obj.set_fahrenheit(32.0);
+    assert_eq!(obj.celsius(), 0.0);
+    assert_eq!(obj.property::<f64>("fahrenheit"), 32.0);
+
+    obj.set_clamped(50);
+    assert_eq!(obj.clamped(), 10);
+}
+
+#[gobject::class(final)]
+mod plain_fields {
+    pub struct PlainFields {
+        #[property(get)]
+        name: String,
+        #[property(get)]
+        count: u32,
+    }
+    impl Default for PlainFields {
+        fn default() -> Self {
+            Self {
+                name: "plain".into(),
+                count: 3,
+            }
+        }
+    }
+}
+
+#[test]
+fn plain_read_only_fields() {
+    let obj = glib::Object::new::<PlainFields>(&[]).unwrap();
+    assert_eq!(obj.name(), "plain");
+    assert_eq!(obj.count(), 3);
+    assert_eq!(obj.property::<String>("name"), "plain");
+}
+
+#[gobject::class(final)]
+mod move_accessors {
+    use std::cell::Cell;
+    use std::marker::PhantomData;
+
+    #[derive(Default)]
+    pub struct MoveAccessors {
+        #[property(get, set)]
+        position: Cell<i32>,
+        #[property(
+            computed,
+            get = "move_target",
+            set = "move |this, value| this.position.set(value)"
+        )]
+        target: PhantomData<i32>,
+    }
+    impl MoveAccessors {
+        fn move_target(&self) -> i32 {
+            self.position.get() + 1
+        }
+    }
+}
+
+#[test]
+fn move_accessors() {
+    let obj = glib::Object::new::<MoveAccessors>(&[]).unwrap();
+    obj.set_target(5);
+    assert_eq!(obj.position(), 5);
+    assert_eq!(obj.property::<i32>("target"), 6);
+}
+
+#[gobject::class(final)]
+mod changed_signal {
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    pub struct ChangedSignal {
+        #[property(get, set, changed_signal = "value-changed")]
+        value: Cell<i32>,
+        #[property(
+            get,
+            set,
+            explicit_notify,
+            lax_validation,
+            changed_signal = "value-changed"
+        )]
+        other: Cell<i32>,
+    }
+    impl ChangedSignal {
+        #[signal]
+        fn value_changed(&self) {}
+    }
+}
+
+#[test]
+fn changed_signal() {
+    let obj = glib::Object::new::<ChangedSignal>(&[]).unwrap();
+    let emitted = std::rc::Rc::new(std::cell::Cell::new(0));
+    obj.connect_value_changed(glib::clone!(@strong emitted => move |_| {
+        emitted.set(emitted.get() + 1);
+    }));
+    obj.set_value(3);
+    assert_eq!(emitted.get(), 1);
+    obj.set_property("value", 4i32);
+    assert_eq!(emitted.get(), 2);
+    obj.set_other(1);
+    obj.set_other(1);
+    assert_eq!(emitted.get(), 3);
+    obj.set_property("other", 2i32);
+    assert_eq!(emitted.get(), 4);
+}
+
+#[test]
+fn changed_signal_unchanged_value() {
+    let obj = glib::Object::new::<ChangedSignal>(&[]).unwrap();
+    let emitted = std::rc::Rc::new(std::cell::Cell::new(0));
+    obj.connect_value_changed(glib::clone!(@strong emitted => move |_| {
+        emitted.set(emitted.get() + 1);
+    }));
+    obj.set_value(3);
+    obj.set_value(3);
+    obj.set_property("value", 3i32);
+    assert_eq!(emitted.get(), 1);
+    obj.set_property("other", 2i32);
+    obj.set_property("other", 2i32);
+    obj.set_other(2);
+    assert_eq!(emitted.get(), 2);
+}
+
+#[gobject::class(final)]
+mod read_write_construct {
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    pub struct RwcProps {
+        #[property(read_write_construct, builder(default_value = 7))]
+        level: Cell<u32>,
+    }
+}
+
+#[test]
+fn read_write_construct() {
+    let obj = glib::Object::new::<RwcProps>(&[("level", &3u32)]).unwrap();
+    let pspec = obj.find_property("level").unwrap();
+    assert_eq!(
+        pspec.flags(),
+        glib::ParamFlags::READABLE | glib::ParamFlags::WRITABLE | glib::ParamFlags::CONSTRUCT
+    );
+    assert_eq!(obj.level(), 3);
+    obj.set_level(4);
+    assert_eq!(obj.level(), 4);
+    let obj = glib::Object::new::<RwcProps>(&[]).unwrap();
+    assert_eq!(obj.level(), 7);
+}
+
+#[test]
+fn filtered_properties() {
+    let names = |pspecs: Vec<&glib::ParamSpec>| {
+        pspecs
+            .into_iter()
+            .map(|p| p.name().to_owned())
+            .collect::<Vec<_>>()
+    };
+    let readable = names(BasicProps::readable_properties());
+    assert!(readable.contains(&"readable-i32".to_owned()));
+    assert!(!readable.contains(&"writable-i32".to_owned()));
+    let writable = names(BasicProps::writable_properties());
+    assert!(writable.contains(&"writable-i32".to_owned()));
+    assert!(!writable.contains(&"readable-i32".to_owned()));
+    assert!(!writable.contains(&"my-construct-only".to_owned()));
+}
+
+#[gobject::class(final)]
+mod downcast {
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    pub struct DowncastProps {
+        #[property(get, set, object, downcast = "Option<super::BasicProps>")]
+        nullable: RefCell<Option<glib::Object>>,
+        #[property(get, set, object, downcast = "super::BasicProps")]
+        required: RefCell<Option<glib::Object>>,
+    }
+}
+
+#[test]
+fn downcast_props() {
+    let obj = glib::Object::new::<DowncastProps>(&[]).unwrap();
+    assert!(obj.nullable().is_none());
+    let basic = glib::Object::new::<BasicProps>(&[]).unwrap();
+    obj.set_nullable(Some(basic.clone().upcast()));
+    assert_eq!(obj.nullable().as_ref(), Some(&basic));
+    obj.set_nullable(Some(glib::Object::new::<glib::Object>(&[]).unwrap()));
+    assert!(obj.nullable().is_none());
+    obj.set_required(Some(basic.clone().upcast()));
+    assert_eq!(obj.required(), basic);
+}
+
+#[test]
+#[should_panic]
+fn downcast_props_mismatch() {
+    let obj = glib::Object::new::<DowncastProps>(&[]).unwrap();
+    obj.set_required(Some(glib::Object::new::<glib::Object>(&[]).unwrap()));
+    obj.required();
+}
+
+#[gobject::class(final)]
+mod nullable_object {
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    pub struct NullableObject {
+        #[property(get, set, object)]
+        child: RefCell<Option<super::BasicProps>>,
+    }
+}
+
+#[test]
+fn nullable_object_props() {
+    let obj = glib::Object::new::<NullableObject>(&[]).unwrap();
+    let pspec = obj.find_property("child").unwrap();
+    assert_eq!(pspec.value_type(), BasicProps::static_type());
+    assert!(obj.child().is_none());
+    assert!(obj.property::<Option<BasicProps>>("child").is_none());
+
+    let child = glib::Object::new::<BasicProps>(&[]).unwrap();
+    obj.set_child(Some(child.clone()));
+    assert_eq!(obj.child().as_ref(), Some(&child));
+    assert_eq!(obj.property::<Option<BasicProps>>("child"), Some(child));
+
+    obj.set_child(None);
+    assert!(obj.child().is_none());
+
+    let child = glib::Object::new::<BasicProps>(&[]).unwrap();
+    obj.set_property("child", &child);
+    assert_eq!(obj.child().as_ref(), Some(&child));
+    obj.set_property("child", None::<BasicProps>);
+    assert!(obj.child().is_none());
+    assert!(obj
+        .property_value("child")
+        .get::<Option<BasicProps>>()
+        .unwrap()
+        .is_none());
+}
+
+#[gobject::class(final)]
+mod snapshot {
+    #[derive(Default)]
+    pub struct SnapshotProps {
+        #[property(get, set, boxed, snapshot)]
+        names: gobject::SnapshotCell<Vec<String>>,
+    }
+}
+
+#[test]
+fn snapshot_props() {
+    let obj = glib::Object::new::<SnapshotProps>(&[]).unwrap();
+    assert!(obj.names().is_empty());
+    obj.set_names(vec!["a".into(), "b".into()]);
+    let before = obj.names();
+    assert!(std::sync::Arc::ptr_eq(&before, &obj.names()));
+
+    obj.set_names(vec!["c".into()]);
+    assert_eq!(*before, ["a", "b"]);
+    assert_eq!(*obj.names(), ["c"]);
+    assert_eq!(obj.property::<Vec<String>>("names"), ["c"]);
+}
+
+#[cfg(feature = "use_gio")]
+#[gobject::class(final)]
+mod list_model {
+    use glib::once_cell::unsync::OnceCell;
+    use glib::subclass::prelude::ObjectImplExt;
+    use glib::{Cast, StaticType};
+
+    #[derive(Default)]
+    pub struct ModelContainer {
+        #[property(get, object)]
+        model: OnceCell<gio::ListModel>,
+        #[property(get, object)]
+        store: OnceCell<gio::ListStore>,
+    }
+    impl ModelContainer {
+        fn constructed(&self, obj: &super::ModelContainer) {
+            self.parent_constructed(obj);
+            let store = gio::ListStore::new(glib::Object::static_type());
+            self.model.set(store.clone().upcast()).unwrap();
+            self.store.set(store).unwrap();
+        }
+    }
+}
+
+#[cfg(feature = "use_gio")]
+#[test]
+fn list_model_property() {
+    use gio::prelude::*;
+
+    let obj = glib::Object::new::<ModelContainer>(&[]).unwrap();
+    let pspec = obj.find_property("model").unwrap();
+    assert_eq!(pspec.value_type(), gio::ListModel::static_type());
+    let pspec = obj.find_property("store").unwrap();
+    assert_eq!(pspec.value_type(), gio::ListStore::static_type());
+
+    obj.store()
+        .append(&glib::Object::new::<glib::Object>(&[]).unwrap());
+    assert_eq!(obj.model().n_items(), 1);
+    let model = obj.property::<gio::ListModel>("model");
+    assert_eq!(model.n_items(), 1);
+}
+
+#[gobject::class]
+mod scoped_vis {
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    pub struct ScopedVis {
+        #[property(get, set)]
+        shared: Cell<u32>,
+        #[property(get, set, vis = "pub(crate)")]
+        internal: Cell<u32>,
+    }
+}
+
+#[gobject::class(final)]
+mod scoped_vis_final {
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    pub struct ScopedVisFinal {
+        #[property(get, set, vis = "pub(crate)")]
+        internal: Cell<u32>,
+    }
+}
+
+#[test]
+fn property_vis() {
+    let obj = glib::Object::new::<ScopedVis>(&[]).unwrap();
+    ScopedVisExt::set_shared(&obj, 1);
+    obj.set_internal(2);
+    assert_eq!(obj.shared(), 1);
+    assert_eq!(ScopedVis::internal(&obj), 2);
+
+    let obj = glib::Object::new::<ScopedVisFinal>(&[]).unwrap();
+    obj.set_internal(3);
+    assert_eq!(obj.internal(), 3);
+}
+
+#[gobject::class]
+mod documented {
+    use std::cell::{Cell, RefCell};
+
+    #[derive(Default)]
+    pub struct Documented {
+        /// The number of items.
+        ///
+        /// Copied onto `count` and `set_count`.
+        #[property(get, set)]
+        count: Cell<u32>,
+        #[doc = "The display label."]
+        #[property(get, set)]
+        label: RefCell<String>,
+    }
+}
+
+#[gobject::class(final)]
+mod documented_final {
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    pub struct DocumentedFinal {
+        /// Whether the object is enabled.
+        #[property(get, set)]
+        enabled: Cell<bool>,
+    }
+}
+
+#[test]
+fn documented_accessors() {
+    let obj = glib::Object::new::<Documented>(&[]).unwrap();
+    obj.set_count(4);
+    obj.set_label("four".into());
+    assert_eq!(obj.count(), 4);
+    assert_eq!(obj.label(), "four");
+
+    let obj = glib::Object::new::<DocumentedFinal>(&[]).unwrap();
+    obj.set_enabled(true);
+    assert!(obj.enabled());
+}
+
+#[glib::flags(name = "TestPermissions")]
+pub enum Permissions {
+    #[flags_value(name = "read")]
+    READ = 0b001,
+    #[flags_value(name = "write")]
+    WRITE = 0b010,
+    #[flags_value(name = "execute")]
+    EXECUTE = 0b100,
+}
+
+impl Default for Permissions {
+    fn default() -> Self {
+        Self::READ
+    }
+}
+
+#[gobject::class(final)]
+mod flags_props {
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    pub struct FlagsProps {
+        #[property(get, set, flags)]
+        permissions: Cell<super::Permissions>,
+    }
+}
+
+#[test]
+fn flags_property() {
+    use glib::StaticType;
+
+    let obj = glib::Object::new::<FlagsProps>(&[]).unwrap();
+    let pspec = obj.find_property("permissions").unwrap();
+    assert_eq!(pspec.value_type(), Permissions::static_type());
+    assert!(pspec.downcast_ref::<glib::ParamSpecFlags>().is_some());
+    assert_eq!(obj.permissions(), Permissions::READ);
+
+    let combined = Permissions::READ | Permissions::EXECUTE;
+    obj.set_property("permissions", combined);
+    assert_eq!(obj.property::<Permissions>("permissions"), combined);
+    assert_eq!(obj.permissions(), combined);
+}
+
+#[gobject::class]
+mod value_accessors {
+    use std::cell::{Cell, RefCell};
+
+    #[derive(Default)]
+    pub struct ValueAccessors {
+        #[property(get, set, value_accessor)]
+        count: Cell<u32>,
+        #[property(get, set, boxed, value_accessor)]
+        point: RefCell<Option<super::Point>>,
+    }
+}
+
+#[test]
+fn value_accessors() {
+    let obj = glib::Object::new::<ValueAccessors>(&[]).unwrap();
+    obj.set_count(7);
+    let value = obj.count_value();
+    assert_eq!(value.type_(), glib::Type::U32);
+    assert_eq!(value.get::<u32>().unwrap(), obj.count());
+
+    assert!(obj.point_value().get::<Option<Point>>().unwrap().is_none());
+    obj.set_point(Some(Point { x: 1.0, y: 2.0 }));
+    let value = obj.point_value();
+    assert_eq!(value.type_(), <Point as glib::StaticType>::static_type());
+    let target = glib::Object::new::<ValueAccessors>(&[]).unwrap();
+    target.set_property_from_value("point", &value);
+    assert_eq!(target.point(), obj.point());
+}
+
+#[gobject::class(final)]
+mod construct_order {
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    pub struct ConstructOrder {
+        // Installed before `scale`, so without `construct_after` it would usually be set first.
+        #[property(
+            get,
+            set = "|this, value: u32| this.scaled.set(value * this.scale.get())",
+            construct_only,
+            construct_after = "scale"
+        )]
+        scaled: Cell<u32>,
+        #[property(get, set, construct_only, builder(default_value = 1))]
+        scale: Cell<u32>,
+    }
+}
+
+#[test]
+fn construct_after() {
+    let obj = glib::Object::new::<ConstructOrder>(&[("scaled", &3u32), ("scale", &4u32)]).unwrap();
+    assert_eq!(obj.scale(), 4);
+    assert_eq!(obj.scaled(), 12);
+
+    let obj = glib::Object::new::<ConstructOrder>(&[("scaled", &3u32)]).unwrap();
+    assert_eq!(obj.scale(), 1);
+    assert_eq!(obj.scaled(), 3);
+}
+
+#[gobject::class(final)]
+#[property_type(super::Animal => enum, super::Point => boxed, super::Permissions => ParamSpecFlags)]
+mod mapped_types {
+    use std::cell::{Cell, RefCell};
+
+    pub struct MappedTypes {
+        #[property(get, set)]
+        animal: Cell<super::Animal>,
+        #[property(get, set)]
+        point: RefCell<Option<super::Point>>,
+        #[property(get, set)]
+        permissions: Cell<super::Permissions>,
+    }
+
+    impl Default for MappedTypes {
+        fn default() -> Self {
+            Self {
+                animal: Cell::new(super::Animal::Goat),
+                point: Default::default(),
+                permissions: Default::default(),
+            }
+        }
+    }
+}
+
+#[test]
+fn property_type_mappings() {
+    let obj = glib::Object::new::<MappedTypes>(&[]).unwrap();
+    let pspec = obj.find_property("animal").unwrap();
+    assert!(pspec.downcast_ref::<glib::ParamSpecEnum>().is_some());
+    let pspec = obj.find_property("point").unwrap();
+    assert!(pspec.downcast_ref::<glib::ParamSpecBoxed>().is_some());
+    let pspec = obj.find_property("permissions").unwrap();
+    assert!(pspec.downcast_ref::<glib::ParamSpecFlags>().is_some());
+
+    obj.set_animal(Animal::Badger);
+    assert_eq!(obj.property::<Animal>("animal"), Animal::Badger);
+    obj.set_property("point", Some(Point { x: 1.0, y: 2.0 }));
+    assert_eq!(obj.point(), Some(Point { x: 1.0, y: 2.0 }));
+}
+
+#[gobject::class(final)]
+mod default_props {
+    use std::cell::{Cell, RefCell};
+
+    #[derive(Default)]
+    pub struct DefaultProps {
+        #[property(get, set, default = "hello")]
+        name: RefCell<String>,
+        #[property(get, set, default = 10)]
+        count: Cell<i32>,
+        #[property(get, set, default = true)]
+        enabled: Cell<bool>,
+    }
+}
+
+#[test]
+fn property_defaults() {
+    let obj = glib::Object::new::<DefaultProps>(&[]).unwrap();
+    assert_eq!(obj.name(), "hello");
+    assert_eq!(obj.count(), 10);
+    assert!(obj.enabled());
+    let pspec = obj.find_property("name").unwrap();
+    assert_eq!(
+        pspec.default_value().get::<Option<String>>().unwrap(),
+        Some(String::from("hello"))
+    );
+    let pspec = obj.find_property("count").unwrap();
+    assert_eq!(pspec.default_value().get::<i32>().unwrap(), 10);
+
+    let obj = glib::Object::new::<DefaultProps>(&[("count", &3i32)]).unwrap();
+    assert_eq!(obj.count(), 3);
+    assert_eq!(obj.name(), "hello");
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, glib::Enum)]
+#[repr(u32)]
+#[enum_type(name = "TestSizeType")]
+pub enum Size {
+    Small,
+    Medium,
+    Large,
+}
+
+impl Default for Size {
+    fn default() -> Self {
+        Self::Medium
+    }
+}
+
+#[gobject::class(final)]
+mod enum_defaults {
+    use std::cell::Cell;
+
+    pub struct EnumDefaults {
+        #[property(get, set, enum)]
+        size: Cell<super::Size>,
+        #[property(get, set, enum, default("super::Size::Large"))]
+        large: Cell<super::Size>,
+        #[property(get, set, enum)]
+        animal: Cell<super::Animal>,
+    }
+
+    impl Default for EnumDefaults {
+        fn default() -> Self {
+            Self {
+                size: Default::default(),
+                large: Default::default(),
+                animal: Cell::new(super::Animal::Goat),
+            }
+        }
+    }
+}
+
+#[test]
+fn enum_property_defaults() {
+    let obj = glib::Object::new::<EnumDefaults>(&[]).unwrap();
+    let default = |name| {
+        obj.find_property(name)
+            .unwrap()
+            .downcast::<glib::ParamSpecEnum>()
+            .unwrap()
+            .default_value()
+    };
+    assert_eq!(default("size"), Size::Medium as i32);
+    assert_eq!(default("large"), Size::Large as i32);
+    assert_eq!(default("animal"), Animal::Goat as i32);
+    assert_eq!(obj.size(), Size::Medium);
+    assert_eq!(obj.large(), Size::Large);
+}
+
+#[gobject::class(final)]
+mod construct_only_props {
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    pub struct ConstructOnlyProps {
+        #[property(get, construct_only)]
+        id: Cell<u32>,
+    }
+}
+
+#[test]
+fn construct_only_without_set() {
+    let obj = glib::Object::new::<ConstructOnlyProps>(&[("id", &7u32)]).unwrap();
+    assert_eq!(obj.id(), 7);
+    let flags = obj.find_property("id").unwrap().flags();
+    assert!(flags.contains(glib::ParamFlags::WRITABLE | glib::ParamFlags::CONSTRUCT_ONLY));
+}
+
+#[gobject::class(final)]
+mod bound_props {
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    pub struct BoundProps {
+        #[property(get, set, bind)]
+        celsius: Cell<f64>,
+        #[property(get, set)]
+        fahrenheit: Cell<f64>,
+    }
+}
+
+#[test]
+fn bind_property_full() {
+    let source = glib::Object::new::<BoundProps>(&[]).unwrap();
+    let target = glib::Object::new::<BoundProps>(&[]).unwrap();
+    source.set_celsius(100.0);
+    let binding = source.bind_celsius_full(
+        &target,
+        "fahrenheit",
+        glib::BindingFlags::BIDIRECTIONAL | glib::BindingFlags::SYNC_CREATE,
+        |_, value| Some((value.get::<f64>().unwrap() * 9.0 / 5.0 + 32.0).to_value()),
+        |_, value| Some(((value.get::<f64>().unwrap() - 32.0) * 5.0 / 9.0).to_value()),
+    );
+    assert_eq!(target.fahrenheit(), 212.0);
+    target.set_fahrenheit(32.0);
+    assert_eq!(source.celsius(), 0.0);
+    source.set_celsius(-40.0);
+    assert_eq!(target.fahrenheit(), -40.0);
+    binding.unbind();
+    source.set_celsius(10.0);
+    assert_eq!(target.fahrenheit(), -40.0);
+}
+
+#[gobject::class(final)]
+mod ranged_props {
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    pub struct RangedProps {
+        #[property(get, set, minimum = 0, maximum = 100)]
+        percent: Cell<i32>,
+        #[property(get, set, minimum = -1.0, maximum = 1.0)]
+        balance: Cell<f64>,
+        #[property(get, set, maximum = 10)]
+        level: Cell<u32>,
+    }
+}
+
+#[test]
+fn property_ranges() {
+    let obj = glib::Object::new::<RangedProps>(&[]).unwrap();
+    let pspec = obj.find_property("percent").unwrap();
+    let pspec = pspec.downcast_ref::<glib::ParamSpecInt>().unwrap();
+    assert_eq!((pspec.minimum(), pspec.maximum()), (0, 100));
+    let pspec = obj.find_property("balance").unwrap();
+    let pspec = pspec.downcast_ref::<glib::ParamSpecDouble>().unwrap();
+    assert_eq!((pspec.minimum(), pspec.maximum()), (-1.0, 1.0));
+    let pspec = obj.find_property("level").unwrap();
+    let pspec = pspec.downcast_ref::<glib::ParamSpecUInt>().unwrap();
+    assert_eq!((pspec.minimum(), pspec.maximum()), (0, 10));
+}
+
+#[gobject::class(final)]
+mod named_accessors {
+    use std::cell::Cell;
+    use std::marker::PhantomData;
+
+    #[derive(Default)]
+    pub struct Rect {
+        #[property(get, set)]
+        width: Cell<u32>,
+        #[property(get, set)]
+        height: Cell<u32>,
+        #[property(computed, get = "compute_area", set = "store_area")]
+        area: PhantomData<u32>,
+    }
+    impl Rect {
+        fn compute_area(&self) -> u32 {
+            self.width.get() * self.height.get()
+        }
+        fn store_area(&self, area: u32) {
+            self.height.set(area / self.width.get().max(1));
+        }
+    }
+}
+
+#[test]
+fn named_property_accessors() {
+    let rect = glib::Object::new::<Rect>(&[("width", &4u32), ("height", &3u32)]).unwrap();
+    assert_eq!(rect.property::<u32>("area"), 12);
+    rect.set_area(20);
+    assert_eq!(rect.height(), 5);
+    rect.set_property("area", 8u32);
+    assert_eq!(rect.height(), 2);
+}
+
+#[gobject::class(final)]
+mod method_props {
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    pub struct Circle {
+        #[property(get, set)]
+        radius: Cell<f64>,
+    }
+    impl Circle {
+        /// The area of the circle.
+        #[property(get, nick = "Area")]
+        fn area(&self) -> f64 {
+            std::f64::consts::PI * self.radius.get() * self.radius.get()
+        }
+    }
+}
+
+#[test]
+fn method_properties() {
+    let circle = glib::Object::new::<Circle>(&[("radius", &2.0f64)]).unwrap();
+    assert_eq!(circle.property::<f64>("area"), std::f64::consts::PI * 4.0);
+    let pspec = circle.find_property("area").unwrap();
+    assert_eq!(pspec.nick(), "Area");
+    assert!(!pspec.flags().contains(glib::ParamFlags::WRITABLE));
+}
+
+#[gobject::class(final)]
+mod weak_props {
+    #[derive(Default)]
+    pub struct TreeNode {
+        #[property(get, set, weak)]
+        parent: glib::WeakRef<super::TreeNode>,
+    }
+}
+
+#[test]
+fn weak_property() {
+    let child = glib::Object::new::<TreeNode>(&[]).unwrap();
+    let pspec = child.find_property("parent").unwrap();
+    let pspec = pspec.downcast::<glib::ParamSpecObject>().unwrap();
+    assert_eq!(pspec.value_type(), TreeNode::static_type());
+    {
+        let parent = glib::Object::new::<TreeNode>(&[]).unwrap();
+        child.set_parent(Some(parent.clone()));
+        assert_eq!(child.parent(), Some(parent));
+    }
+    assert!(child.parent().is_none());
+}
+
+#[gobject::class(final)]
+mod builder_closure_props {
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    pub struct BuilderClosure {
+        #[property(get, set, builder = "|b| b.minimum(-5).maximum(5)")]
+        offset: Cell<i32>,
+    }
+}
+
+#[test]
+fn builder_closure() {
+    let obj = glib::Object::new::<BuilderClosure>(&[]).unwrap();
+    let pspec = obj.find_property("offset").unwrap();
+    let pspec = pspec.downcast::<glib::ParamSpecInt>().unwrap();
+    assert_eq!((pspec.minimum(), pspec.maximum()), (-5, 5));
+    assert_eq!(pspec.nick(), "offset");
+}
+
+glib::wrapper! {
+    pub struct ManualSubclass(ObjectSubclass<manual_subclass::ManualSubclass>);
+}
+
+mod manual_subclass {
+    use glib::subclass::prelude::*;
+    use std::cell::Cell;
+
+    #[derive(Default, gobject::Properties)]
+    #[properties(final_type = "super::ManualSubclass")]
+    pub struct ManualSubclass {
+        #[property(get, set, vis = "pub")]
+        count: Cell<u32>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ManualSubclass {
+        const NAME: &'static str = "ManualSubclass";
+        type Type = super::ManualSubclass;
+    }
+
+    impl ObjectImpl for ManualSubclass {
+        fn properties() -> &'static [glib::ParamSpec] {
+            Self::derived_properties()
+        }
+        fn set_property(
+            &self,
+            obj: &Self::Type,
+            id: usize,
+            value: &glib::Value,
+            pspec: &glib::ParamSpec,
+        ) {
+            self.derived_set_property(obj, id, value, pspec)
+        }
+        fn property(&self, obj: &Self::Type, id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+            self.derived_get_property(obj, id, pspec)
+        }
+    }
+}
+
+#[test]
+fn derived_properties_on_manual_subclass() {
+    let obj = glib::Object::new::<ManualSubclass>(&[("count", &3u32)]).unwrap();
+    assert_eq!(obj.count(), 3);
+    obj.set_count(4);
+    assert_eq!(obj.property::<u32>("count"), 4);
+}
+
+#[test]
+fn documented_accessors_expansion() {
+    use gobject_core::{util::Errors, ClassDefinition, ClassOptions};
+    use quote::{quote, ToTokens};
+
+    let expand = |opts| {
+        let errors = Errors::new();
+        let opts = ClassOptions::parse(opts, &errors);
+        let module = syn::parse_quote! {
+            mod documented {
+                #[derive(Default)]
+                pub struct Documented {
+                    /// The number of items.
+                    #[property(get, set)]
+                    count: std::cell::Cell<u32>,
+                }
+            }
+        };
+        let mut class = ClassDefinition::parse(module, opts, syn::parse_quote!(gobject), &errors);
+        class.add_private_items();
+        assert!(errors.into_compile_errors().is_none());
+        class.to_token_stream().to_string()
+    };
+
+    let tokens = expand(quote! {});
+    assert!(tokens.contains(r#"# [doc = r" The number of items."] fn count (& self)"#));
+    assert!(tokens.contains(r#"# [doc = r" The number of items."] fn set_count (& self"#));
+
+    let tokens = expand(quote! { final });
+    for method in ["pub fn count (& self)", "pub fn set_count (& self"] {
+        let start = tokens.find(method).unwrap();
+        let (_, body) = tokens[start..].split_once('{').unwrap();
+        assert!(body.starts_with(r#" # ! [doc = r" The number of items."] # ! [inline]"#));
+    }
+}
+
+#[test]
+fn writable_plain_field_errors() {
+    use gobject_core::{util::Errors, ClassDefinition, ClassOptions};
+    use syn::parse_quote;
+
+    let check = |attr: syn::Attribute, ty: syn::Type| {
+        let errors = Errors::new();
+        let opts = ClassOptions::parse(quote::quote! { final }, &errors);
+        let module = parse_quote! {
+            mod plain {
+                #[derive(Default)]
+                pub struct Plain {
+                    #attr
+                    value: #ty,
+                }
+            }
+        };
+        ClassDefinition::parse(module, opts, parse_quote!(gobject), &errors);
+        errors.into_compile_errors().map(|e| e.to_string())
+    };
+
+    let writable: syn::Attribute = parse_quote!(#[property(get, set)]);
+    let message = "Writable property field must be a storage type with interior mutability";
+    let plain_types: [syn::Type; 4] = [
+        parse_quote!(u32),
+        parse_quote!(String),
+        parse_quote!(Option<String>),
+        parse_quote!(std::collections::HashMap<String, u32>),
+    ];
+    for ty in plain_types {
+        let errors = check(writable.clone(), ty).unwrap();
+        assert!(errors.contains(message), "{}", errors);
+    }
+    assert!(check(parse_quote!(#[property(get)]), parse_quote!(String)).is_none());
+    assert!(check(writable.clone(), parse_quote!(std::cell::Cell<u32>)).is_none());
+    assert!(check(writable, parse_quote!(std::sync::atomic::AtomicU32)).is_none());
+}
@@ -109,3 +109,41 @@ mod obj_final3 {
     }
     impl super::ObjDerivableImpl for ObjFinal3 {}
 }
+
+#[gobject::class(final)]
+mod settings {
+    use std::cell::{Cell, RefCell};
+
+    #[derive(Default)]
+    #[gobject_serde(serialize, deserialize)]
+    pub struct Settings {
+        #[property(get, set)]
+        volume: Cell<u32>,
+        #[property(get, set)]
+        muted: Cell<bool>,
+        #[property(get, set)]
+        device: RefCell<String>,
+        #[property(get, set, object)]
+        #[serde(skip)]
+        owner: RefCell<Option<glib::Object>>,
+    }
+}
+
+#[test]
+fn settings_round_trip() {
+    let obj = glib::Object::new::<Settings>(&[]).unwrap();
+    obj.set_volume(11);
+    obj.set_muted(true);
+    obj.set_device("hw:0".into());
+    obj.set_owner(Some(glib::Object::new::<glib::Object>(&[]).unwrap()));
+    let saved = serde_json::to_value(&obj).unwrap();
+    assert_eq!(
+        saved,
+        serde_json::json!({ "volume": 11, "muted": true, "device": "hw:0" })
+    );
+    let restored: Settings = serde_json::from_value(saved).unwrap();
+    assert_eq!(restored.volume(), 11);
+    assert!(restored.muted());
+    assert_eq!(restored.device(), "hw:0");
+    assert!(restored.owner().is_none());
+}
@@ -65,6 +65,14 @@ mod obj_signals {
                 .unwrap_or_else(|| format!("first({})", val));
             ControlFlow::Continue(Some(new))
         }
+        #[signal(default_return = "String::from(\"unhandled\")")]
+        fn describe(&self, id: u32) -> String {}
+        #[signal]
+        fn count(&self) -> u32 {}
+        #[signal]
+        fn renamed(&self, old: &str, new: &str) {}
+        #[signal]
+        fn selection_changed(&self, item: Option<glib::Object>) {}
     }
 }
 
@@ -111,4 +119,192 @@ fn signals() {
         signals.emit_string_appender("b"),
         "first(class(b)), add(closure(b))"
     );
+
+    assert_eq!(signals.emit_describe(1), "unhandled");
+    let id = signals.connect_describe(|_, id| format!("item {}", id));
+    assert_eq!(signals.emit_describe(2), "item 2");
+    glib::ObjectExt::disconnect(&signals, id);
+    assert_eq!(signals.emit_describe(3), "unhandled");
+
+    assert_eq!(signals.emit_count(), 0);
+    signals.connect_count(|_| 42);
+    assert_eq!(signals.emit_count(), 42);
+}
+
+#[test]
+fn string_args() {
+    use glib::subclass::prelude::*;
+
+    let signals = glib::Object::new::<Signals>(&[]).unwrap();
+    signals.connect_renamed(|sig, old, new| {
+        sig.imp().append(&format!("{} -> {}", old, new));
+    });
+    let new = String::from("b");
+    signals.emit_renamed("a", &new);
+    assert_eq!(*signals.imp().log.borrow(), &["a -> b"]);
+}
+
+#[test]
+fn nullable_object_args() {
+    use glib::subclass::prelude::*;
+
+    let signals = glib::Object::new::<Signals>(&[]).unwrap();
+    signals.connect_selection_changed(|sig, item| {
+        let msg = match item {
+            Some(item) => glib::ObjectExt::type_(&item).name().to_owned(),
+            None => String::from("none"),
+        };
+        sig.imp().append(&msg);
+    });
+    signals.emit_selection_changed(None);
+    let item = glib::Object::new::<glib::Object>(&[]).unwrap();
+    signals.emit_selection_changed(Some(item));
+    assert_eq!(*signals.imp().log.borrow(), &["none", "GObject"]);
+}
+
+#[gobject::class(final, sync)]
+mod obj_dispatch {
+    #[derive(Default)]
+    pub struct Dispatch {}
+    impl Dispatch {
+        #[signal(dispatch_main)]
+        fn progress(&self, value: u32, label: &str) {}
+    }
+}
+
+#[test]
+fn dispatch_main() {
+    use std::sync::{Arc, Mutex};
+
+    let context = glib::MainContext::default();
+    let _guard = context.acquire().unwrap();
+    let obj = glib::Object::new::<Dispatch>(&[]).unwrap();
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let r = received.clone();
+    obj.connect_progress(move |_, value, label| {
+        r.lock()
+            .unwrap()
+            .push((value, label.to_owned(), std::thread::current().id()));
+    });
+    let o = obj.clone();
+    std::thread::spawn(move || o.emit_progress(50, "half"))
+        .join()
+        .unwrap();
+    assert!(received.lock().unwrap().is_empty());
+    while context.iteration(false) {}
+    assert_eq!(
+        *received.lock().unwrap(),
+        [(50, "half".to_owned(), std::thread::current().id())]
+    );
+}
+
+#[gobject::class(final, sync)]
+mod obj_sync_local {
+    #[derive(Default)]
+    pub struct SyncLocal {}
+    impl SyncLocal {
+        #[signal]
+        fn changed(&self, value: u32) {}
+    }
+}
+
+#[test]
+fn sync_connect_local() {
+    use std::{cell::Cell, rc::Rc};
+
+    let obj = glib::Object::new::<SyncLocal>(&[]).unwrap();
+    // `Rc` is neither `Send` nor `Sync`, so only the `_local` variant accepts this handler.
+    let total = Rc::new(Cell::new(0));
+    let t = total.clone();
+    obj.connect_changed_local(move |_, value| t.set(t.get() + value));
+    obj.emit_changed(2);
+    obj.emit_changed(3);
+    assert_eq!(total.get(), 5);
+}
+
+fn stop_on_true(
+    _hint: &glib::subclass::signal::SignalInvocationHint,
+    accu: &mut glib::Value,
+    value: &glib::Value,
+) -> bool {
+    let handled = value.get::<bool>().unwrap();
+    *accu = glib::ToValue::to_value(&handled);
+    !handled
+}
+
+#[gobject::class(final)]
+mod raw_accumulator {
+    #[derive(Default)]
+    pub struct RawAccumulator {}
+    impl RawAccumulator {
+        #[signal(run_last, accumulator = "super::stop_on_true")]
+        fn event(&self, code: u32) -> bool {
+            code == 0
+        }
+    }
+}
+
+#[test]
+fn raw_signal_accumulator() {
+    use std::{cell::Cell, rc::Rc};
+
+    let obj = glib::Object::new::<RawAccumulator>(&[]).unwrap();
+    let calls = Rc::new(Cell::new(0));
+    obj.connect_event({
+        let calls = calls.clone();
+        move |_, code| {
+            calls.set(calls.get() + 1);
+            code == 1
+        }
+    });
+    assert!(obj.emit_event(1));
+    assert_eq!(calls.get(), 1);
+    assert!(obj.emit_event(0));
+    assert_eq!(calls.get(), 2);
+    assert!(!obj.emit_event(2));
+    assert_eq!(calls.get(), 3);
+}
+
+#[gobject::class(final)]
+mod obj_deprecated {
+    #[derive(Default)]
+    pub struct ObjDeprecated {}
+    impl ObjDeprecated {
+        #[signal(deprecated = "use `renamed` instead")]
+        fn legacy(&self) {}
+        #[signal(deprecated)]
+        fn old(&self, value: u32) -> u32 {
+            value
+        }
+    }
+}
+
+#[gobject::class]
+mod obj_deprecated_derivable {
+    #[derive(Default)]
+    pub struct ObjDeprecatedDerivable {}
+    impl ObjDeprecatedDerivable {
+        #[signal(deprecated)]
+        fn ancient(&self) {}
+    }
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_signals() {
+    use glib::subclass::SignalId;
+
+    let obj = glib::Object::new::<ObjDeprecated>(&[]).unwrap();
+    for name in ["legacy", "old"] {
+        let id = SignalId::lookup(name, ObjDeprecated::static_type()).unwrap();
+        assert!(id.query().flags().contains(glib::SignalFlags::DEPRECATED));
+    }
+    obj.connect_legacy(|_| {});
+    obj.emit_legacy();
+    assert_eq!(obj.emit_old(3), 3);
+
+    let derivable = glib::Object::new::<ObjDeprecatedDerivable>(&[]).unwrap();
+    let id = SignalId::lookup("ancient", ObjDeprecatedDerivable::static_type()).unwrap();
+    assert!(id.query().flags().contains(glib::SignalFlags::DEPRECATED));
+    derivable.emit_ancient();
 }
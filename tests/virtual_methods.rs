@@ -126,3 +126,269 @@ fn virtual_methods() {
         "overridden again: (2000 World Hello)"
     );
 }
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(
+    expected = "virtual method `virtual_concat` called on `ObjDerivable`, expected an instance of `Implementor`"
+)]
+fn mismatched_virtual_method_instance() {
+    use glib::{Cast, ObjectExt};
+
+    let obj = glib::Object::new::<Implementor>(&[]).unwrap();
+    let other = glib::Object::new::<ObjDerivable>(&[]).unwrap();
+    let class: &obj_abstract::ObjAbstractClass = obj.upcast_ref::<ObjAbstract>().class().as_ref();
+    (class.virtual_concat)(other.upcast_ref(), "Hello", "World");
+}
+
+#[gobject::class(impl_trait = "RenamedBaseImplementation")]
+mod obj_renamed_base {
+    #[derive(Default)]
+    pub struct RenamedBase {}
+    impl RenamedBase {
+        #[virt]
+        fn greeting(&self) -> String {
+            "hello".into()
+        }
+    }
+}
+
+#[gobject::class(final, extends(RenamedBase))]
+mod obj_renamed_child {
+    #[derive(Default)]
+    pub struct RenamedChild {}
+    impl super::RenamedBaseImplementation for RenamedChild {
+        fn greeting(&self, obj: &Self::Type) -> String {
+            format!(
+                "{} world",
+                super::RenamedBaseImplExt::parent_greeting(self, obj)
+            )
+        }
+    }
+}
+
+#[test]
+fn renamed_impl_trait() {
+    let obj = glib::Object::new::<RenamedChild>(&[]).unwrap();
+    assert_eq!(obj.greeting(), "hello world");
+}
+
+#[gobject::class(abstract)]
+mod obj_summer {
+    #[derive(Default)]
+    pub struct Summer {}
+    impl Summer {
+        #[virt]
+        fn virtual_sum(&self, values: &[u32]) -> u32 {
+            values.iter().sum()
+        }
+    }
+}
+
+#[gobject::class(final, extends(Summer))]
+mod obj_weighted_summer {
+    #[derive(Default)]
+    pub struct WeightedSummer {}
+    impl super::SummerImpl for WeightedSummer {
+        fn virtual_sum(&self, obj: &Self::Type, values: &[u32]) -> u32 {
+            let first = values.first().copied().unwrap_or_default();
+            first * 100 + super::SummerImplExt::parent_virtual_sum(self, obj, values)
+        }
+    }
+}
+
+#[test]
+fn slice_arguments() {
+    let obj = glib::Object::new::<WeightedSummer>(&[]).unwrap();
+    assert_eq!(obj.virtual_sum(&[1, 2, 3]), 106);
+    assert_eq!(obj.virtual_sum(&[]), 0);
+}
+
+#[gobject::class(abstract)]
+mod obj_sizable {
+    #[derive(Default)]
+    pub struct Sizable {}
+    impl Sizable {
+        #[virt]
+        fn preferred_width(&self, for_height: i32) -> i32 {}
+        #[virt]
+        fn label(&self) -> String {}
+    }
+}
+
+#[gobject::class(final, extends(Sizable))]
+mod obj_fixed_size {
+    #[derive(Default)]
+    pub struct FixedSize {}
+    impl super::SizableImpl for FixedSize {}
+}
+
+#[test]
+fn empty_virtual_method_default() {
+    let obj = glib::Object::new::<FixedSize>(&[]).unwrap();
+    assert_eq!(obj.preferred_width(100), 0);
+    assert_eq!(obj.label(), "");
+}
+
+#[gobject::class]
+mod obj_signal_base {
+    #[derive(Default)]
+    pub struct SignalBase {}
+    impl SignalBase {
+        #[signal]
+        fn with_handler(&self) -> i32 {
+            100
+        }
+        #[signal]
+        fn no_handler(&self) -> i32 {}
+    }
+}
+
+#[gobject::class(final, extends(SignalBase))]
+mod obj_signal_chain {
+    #[derive(Default)]
+    pub struct SignalChain {}
+    impl SignalChain {
+        #[signal(override)]
+        fn with_handler(&self) -> i32 {
+            1 + self.parent_with_handler()
+        }
+        #[signal(override)]
+        fn no_handler(&self) -> i32 {
+            7 + self.parent_no_handler()
+        }
+    }
+    impl super::SignalBaseImpl for SignalChain {}
+}
+
+#[test]
+fn chain_missing_parent_signal_handler() {
+    let obj = glib::Object::new::<SignalChain>(&[]).unwrap();
+    assert_eq!(obj.emit_with_handler(), 101);
+    assert_eq!(obj.emit_no_handler(), 7);
+}
+
+#[gobject::class(final, extends(ObjAbstract))]
+mod obj_parent_default {
+    use std::cell::Cell;
+    #[derive(Default)]
+    pub struct ParentDefault {
+        #[property(get, set, override_class = "super::ObjAbstract", parent_default = 42)]
+        my_prop: Cell<u64>,
+    }
+    impl super::ObjAbstractImpl for ParentDefault {}
+}
+
+#[gobject::class]
+mod obj_construct_parent {
+    use std::cell::Cell;
+    #[derive(Default)]
+    pub struct ConstructParent {
+        #[property(get, set, construct)]
+        level: Cell<u32>,
+    }
+}
+
+#[gobject::class(final, extends(ConstructParent))]
+mod obj_construct_parent_default {
+    use std::cell::Cell;
+    #[derive(Default)]
+    pub struct ConstructParentDefault {
+        #[property(
+            get,
+            set,
+            override_class = "super::ConstructParent",
+            parent_default = 3
+        )]
+        level: Cell<u32>,
+    }
+    impl super::ConstructParentImpl for ConstructParentDefault {}
+}
+
+#[test]
+fn parent_default() {
+    use glib::ObjectExt;
+
+    let obj = glib::Object::new::<ParentDefault>(&[]).unwrap();
+    assert_eq!(obj.my_prop(), 42);
+    let pspec = obj.find_property("my-prop").unwrap();
+    assert_eq!(pspec.default_value().get::<u64>().unwrap(), 42);
+    let obj = glib::Object::new::<ParentDefault>(&[("my-prop", &7u64)]).unwrap();
+    assert_eq!(obj.my_prop(), 7);
+
+    let obj = glib::Object::new::<ConstructParentDefault>(&[]).unwrap();
+    assert_eq!(obj.level(), 3);
+    let pspec = obj.find_property("level").unwrap();
+    assert_eq!(pspec.default_value().get::<u32>().unwrap(), 3);
+    assert!(pspec.flags().contains(glib::ParamFlags::CONSTRUCT));
+}
+
+#[gobject::class(abstract)]
+mod obj_loader {
+    #[derive(Default)]
+    pub struct Loader {}
+    impl Loader {
+        #[virt]
+        async fn load(&self, path: &str) -> String {
+            format!("loaded {}", path)
+        }
+        #[virt]
+        async fn count(&self, values: &[u32]) -> usize {
+            values.len()
+        }
+    }
+}
+
+#[gobject::class(final, extends(Loader))]
+mod obj_custom_loader {
+    #[derive(Default)]
+    pub struct CustomLoader {}
+    #[gobject::async_trait::async_trait(?Send)]
+    impl super::LoaderImpl for CustomLoader {
+        async fn load(&self, obj: &Self::Type, path: &str) -> String {
+            let parent = super::LoaderImplExt::parent_load(self, obj, path).await;
+            format!("custom {}", parent)
+        }
+    }
+}
+
+#[test]
+fn async_virtual_methods() {
+    let obj = glib::Object::new::<CustomLoader>(&[]).unwrap();
+    glib::MainContext::default().block_on(async move {
+        assert_eq!(obj.load("a").await, "custom loaded a");
+        assert_eq!(obj.count(&[1, 2]).await, 2);
+    });
+}
+
+#[gobject::class(
+    extends(glib::Object),
+    parent_trait = "glib::subclass::prelude::ObjectImpl"
+)]
+mod obj_object_parent {
+    #[derive(Default)]
+    pub struct ObjectParent {}
+    impl ObjectParent {
+        #[virt]
+        fn describe(&self) -> String {
+            "object parent".into()
+        }
+    }
+}
+
+#[test]
+fn explicit_object_parent() {
+    let obj = glib::Object::new::<ObjectParent>(&[]).unwrap();
+    assert_eq!(obj.describe(), "object parent");
+}
+
+#[test]
+fn upcast_methods() {
+    use glib::ObjectExt;
+
+    let obj = glib::Object::new::<Implementor2>(&[]).unwrap();
+    let derivable: &ObjDerivable = obj.upcast_obj_derivable();
+    let abstract_: &ObjAbstract = obj.upcast_obj_abstract();
+    assert_eq!(derivable, abstract_);
+    assert_eq!(abstract_.type_(), Implementor2::static_type());
+}